@@ -1,4 +1,4 @@
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 #![feature(type_alias_impl_trait, const_async_blocks)]
 #![warn(
     clippy::complexity,
@@ -9,14 +9,17 @@
     rust_2018_idioms
 )]
 
+use core::fmt::{self, Write};
+
 use asr::{
     file_format::pe,
     future::{next_tick, retry},
+    settings::{gui::Title, Gui},
     signature::Signature,
     time::Duration,
     timer::{self, TimerState},
     watcher::{Pair, Watcher},
-    Address, Address32, Process, settings::Gui,
+    Address, Address32, Process, ProcessId,
 };
 
 asr::panic_handler!();
@@ -24,18 +27,52 @@ asr::async_main!(nightly);
 
 async fn main() {
     let mut settings = Settings::register();
+    let mut rejected_process = None;
 
     loop {
-        // Hook to the target process
-        let process = Process::wait_attach(PROCESS_NAME).await;
+        // Hook to the target process. The executable name is taken from the settings, so
+        // they need to be kept up to date while we wait for the game to be launched
+        let (process, process_id, process_name) = 'attach: loop {
+            settings.update();
+            for &process_name in settings.executable.process_names() {
+                if let Some((process, process_id)) = attach(process_name, rejected_process) {
+                    let mut message = VariableBuffer::<64>::new();
+                    let _ = write!(message, "Attached to {process_name}");
+                    asr::print_message(message.as_str());
+                    break 'attach (process, process_id, process_name);
+                }
+            }
+            next_tick().await;
+        };
 
         process
             .until_closes(async {
                 // Once the target has been found and attached to, set up some default watchers
                 let mut watchers = Watchers::default();
+                let mut loading_state = LoadingState::default();
+                let mut watchdog = Watchdog::default();
+                let mut standby = Standby::default();
 
-                // Perform memory scanning to look for the addresses we need
-                let addresses = retry(|| Addresses::init(&process)).await;
+                // Perform memory scanning to look for the addresses we need. If they can't be
+                // found, we may be attached to the wrong instance of the game (e.g. a crashed
+                // process that never closed), so another one is tried instead.
+                let mut addresses = None;
+                for _ in 0..ADDRESSES_INIT_ATTEMPTS {
+                    addresses = Addresses::init(&process, process_name);
+                    if addresses.is_some() {
+                        break;
+                    }
+                    next_tick().await;
+                }
+                let Some(mut addresses) = addresses else {
+                    asr::print_message("Couldn't find the addresses, trying another process");
+                    rejected_process = Some(process_id);
+                    return;
+                };
+                timer::set_variable("Build", addresses.build.format().as_str());
+                if settings.debug_addresses {
+                    addresses.publish_variables();
+                }
 
                 loop {
                     // Splitting logic. Adapted from OG LiveSplit:
@@ -45,39 +82,84 @@ async fn main() {
                     // 3. If reset does not return true, then the split action will be run.
                     // 4. If the timer is currently not running (and not paused), then the start action will be run.
                     settings.update();
-                    update_loop(&process, &addresses, &mut watchers);
 
-                    let timer_state = timer::state();
-                    if timer_state == TimerState::Running || timer_state == TimerState::Paused {
-                        if let Some(is_loading) = is_loading(&watchers, &settings) {
-                            if is_loading {
-                                timer::pause_game_time()
-                            } else {
-                                timer::resume_game_time()
+                    // While the game doesn't respond to memory reads (e.g. when it's
+                    // minimized), there is nothing to update, so we poll less often
+                    if standby.update(&process, &addresses, settings.standby_on_stall) {
+                        next_tick().await;
+                        continue;
+                    }
+
+                    // If the addresses we resolved are consistently reading nonsense, the
+                    // pointers have likely gone stale, so we need to scan for them again
+                    if !watchdog.check(&process, process_name, &addresses) {
+                        asr::print_message(
+                            "Sentinel reads keep failing, scanning for the addresses again",
+                        );
+                        addresses = retry(|| Addresses::init(&process, process_name)).await;
+                        watchdog = Watchdog::default();
+                        if settings.debug_addresses {
+                            addresses.publish_variables();
+                        }
+                    } else if let Some(id) = watchdog.take_broken_address() {
+                        let mut message = VariableBuffer::<64>::new();
+                        if addresses.resolve(&process, id) {
+                            let _ = write!(message, "Resolved the {} address again", id.name());
+                            if settings.debug_addresses {
+                                addresses.publish_variables();
                             }
+                        } else {
+                            let _ =
+                                write!(message, "Couldn't resolve the {} address again", id.name());
                         }
+                        asr::print_message(message.as_str());
+                    }
 
-                        if let Some(game_time) = game_time(&watchers, &settings, &addresses) {
-                            timer::set_game_time(game_time)
-                        }
+                    update_loop(&process, &addresses, &mut watchers, &settings);
+                    update_variables(&watchers, &settings);
 
-                        if reset(&watchers, &settings) {
-                            timer::reset()
-                        } else if split(&watchers, &settings) {
-                            timer::split()
-                        }
+                    let timer_state = timer::state();
+                    if timer_state == TimerState::Running || timer_state == TimerState::Paused {
+                        let loading_action = is_loading(&watchers, &settings)
+                            .and_then(|is_loading| loading_state.update(is_loading));
+                        settings.split_timing.sequence(
+                            || {
+                                if let Some(action) = loading_action {
+                                    action.apply()
+                                }
+                            },
+                            || {
+                                // The split ending the run is decided first, so it records the
+                                // final game time
+                                let reset = reset(&watchers, &settings, timer_state);
+                                let split = !reset && auto_split(&mut watchers, &settings);
+
+                                if let Some(game_time) = game_time(&watchers, &settings) {
+                                    timer::set_game_time(game_time)
+                                }
+
+                                if reset {
+                                    timer::reset()
+                                } else if split {
+                                    timer::split()
+                                }
+                            },
+                        );
+                    } else if timer_state == TimerState::Ended
+                        && post_credits_reset(&watchers, &settings, timer_state)
+                    {
+                        timer::reset()
                     }
 
-                    if timer::state() == TimerState::NotRunning && start(&watchers, &settings) {
+                    // The state from before a reset is checked, so the run can't start again on
+                    // the same tick it was reset, while the start indicators may still be set
+                    if timer_state == TimerState::NotRunning && start(&watchers, &settings) {
                         timer::start();
-                        timer::pause_game_time();
 
-                        if let Some(is_loading) = is_loading(&watchers, &settings) {
-                            if is_loading {
-                                timer::pause_game_time()
-                            } else {
-                                timer::resume_game_time()
-                            }
+                        let is_loading = is_loading(&watchers, &settings).unwrap_or(true);
+                        match loading_state.start(is_loading, settings.override_game_time) {
+                            Some(action) => action.apply(),
+                            None => publish_load_removal(false),
                         }
                     }
 
@@ -85,20 +167,307 @@ async fn main() {
                 }
             })
             .await;
+
+        // The game may have closed while the splitter was in standby
+        asr::set_tick_rate(DEFAULT_TICK_RATE);
+    }
+}
+
+/// Attaches to a running instance of the game. Any instance other than the one previously
+/// rejected is preferred, falling back to the rejected one if it's the only one left.
+fn attach(process_name: &str, rejected: Option<ProcessId>) -> Option<(Process, ProcessId)> {
+    let mut fallback = None;
+    for process_id in Process::list_by_name(process_name)? {
+        if Some(process_id) == rejected {
+            fallback = Some(process_id);
+        } else if let Some(process) = Process::attach_by_pid(process_id) {
+            return Some((process, process_id));
+        }
+    }
+
+    let process_id = fallback?;
+    Some((Process::attach_by_pid(process_id)?, process_id))
+}
+
+/// Keeps track of the loading state applied to the timer. A change in the loading state
+/// needs to hold for a few ticks before game time gets paused or resumed, in order to
+/// avoid stuttering when the state rapidly toggles across a boundary.
+#[derive(Clone, Copy, Default)]
+struct LoadingState {
+    applied: Option<bool>,
+    pending_ticks: u8,
+}
+
+/// Change to apply to the game time of the timer
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LoadingAction {
+    Pause,
+    Resume,
+}
+
+impl LoadingAction {
+    fn apply(self) {
+        match self {
+            Self::Pause => timer::pause_game_time(),
+            Self::Resume => timer::resume_game_time(),
+        }
+        publish_load_removal(self == Self::Pause);
+    }
+}
+
+/// Reports whether load removal currently keeps game time paused
+fn publish_load_removal(paused: bool) {
+    timer::set_variable("Load Removal", if paused { "Paused" } else { "Running" });
+}
+
+/// Decides whether game time needs to be paused or resumed, given the previous loading state
+/// and whether the game is currently loading. Returns the new loading state along with the
+/// action to apply to the timer, if any.
+const fn loading_action(
+    state: LoadingState,
+    is_loading: bool,
+) -> (LoadingState, Option<LoadingAction>) {
+    if matches!(state.applied, Some(applied) if applied == is_loading) {
+        return (
+            LoadingState {
+                pending_ticks: 0,
+                ..state
+            },
+            None,
+        );
+    }
+
+    let pending_ticks = state.pending_ticks.saturating_add(1);
+    if state.applied.is_some() && pending_ticks < LOADING_DEBOUNCE_TICKS {
+        return (
+            LoadingState {
+                pending_ticks,
+                ..state
+            },
+            None,
+        );
+    }
+
+    (
+        LoadingState {
+            applied: Some(is_loading),
+            pending_ticks: 0,
+        },
+        Some(if is_loading {
+            LoadingAction::Pause
+        } else {
+            LoadingAction::Resume
+        }),
+    )
+}
+
+impl LoadingState {
+    /// Returns the action to apply to the timer, if the loading state needs to change
+    fn update(&mut self, is_loading: bool) -> Option<LoadingAction> {
+        let (state, action) = loading_action(*self, is_loading);
+        *self = state;
+        action
+    }
+
+    /// Records a loading state that has been applied to the timer directly
+    fn force(&mut self, is_loading: bool) {
+        self.applied = Some(is_loading);
+        self.pending_ticks = 0;
+    }
+
+    /// Records the loading state as the timer starts, returning the action to apply to it.
+    /// Game time runs as soon as the timer starts, so it only needs to be paused once if the
+    /// game is loading, without toggling it back and forth. Without the in-game time
+    /// overriding it, game time keeps running from the start, while the loading state is
+    /// still recorded so that it doesn't get paused a few ticks later either.
+    fn start(&mut self, is_loading: bool, override_game_time: bool) -> Option<LoadingAction> {
+        self.force(is_loading);
+        (override_game_time && is_loading).then_some(LoadingAction::Pause)
+    }
+}
+
+/// Periodically verifies that the game's module is still present and that the resolved
+/// addresses still read sensible values, in order to detect stale pointers over long sessions.
+#[derive(Default)]
+struct Watchdog {
+    ticks: u16,
+    failures: u8,
+    address_failures: [u8; AddressId::ALL.len()],
+    broken_address: Option<AddressId>,
+}
+
+impl Watchdog {
+    /// Returns false once the sentinel reads have failed too many times in a row
+    fn check(&mut self, game: &Process, process_name: &str, addresses: &Addresses) -> bool {
+        self.ticks += 1;
+        if self.ticks < WATCHDOG_INTERVAL_TICKS {
+            return true;
+        }
+        self.ticks = 0;
+
+        // Each address is also checked on its own, so a single one that can't be read
+        // anymore can be resolved again without scanning for all of them
+        for (id, failures) in AddressId::ALL.into_iter().zip(&mut self.address_failures) {
+            if game.read::<u8>(addresses.get(id)).is_ok() {
+                *failures = 0;
+            } else {
+                *failures = failures.saturating_add(1);
+                if *failures >= WATCHDOG_FAILURE_THRESHOLD {
+                    *failures = 0;
+                    self.broken_address.get_or_insert(id);
+                }
+            }
+        }
+
+        let healthy = game.get_module_address(process_name).is_ok()
+            && game
+                .read::<u8>(addresses.mode_select)
+                .is_ok_and(|mode| mode <= 3);
+
+        if healthy {
+            if self.failures != 0 {
+                asr::print_message("Sentinel reads recovered");
+            }
+            self.failures = 0;
+        } else {
+            if self.failures == 0 {
+                asr::print_message("Sentinel reads failed");
+            }
+            self.failures = self.failures.saturating_add(1);
+        }
+
+        self.failures < WATCHDOG_FAILURE_THRESHOLD
+    }
+
+    /// Returns an address that has been failing to be read for a while, if any
+    fn take_broken_address(&mut self) -> Option<AddressId> {
+        self.broken_address.take()
+    }
+}
+
+/// Lowers the polling frequency while the memory reads of the game keep failing,
+/// restoring it as soon as the reads recover
+#[derive(Default)]
+struct Standby {
+    active: bool,
+    failed_ticks: u16,
+}
+
+impl Standby {
+    /// Returns whether the splitter is in standby
+    fn update(&mut self, game: &Process, addresses: &Addresses, enabled: bool) -> bool {
+        if game.read::<u8>(addresses.mode_select).is_ok() {
+            self.failed_ticks = 0;
+        } else {
+            self.failed_ticks = self.failed_ticks.saturating_add(1);
+        }
+
+        let stalled = enabled && self.failed_ticks >= STANDBY_THRESHOLD_TICKS;
+        if stalled != self.active {
+            self.active = stalled;
+            if stalled {
+                asr::print_message("Memory reads stalled, entering standby");
+                asr::set_tick_rate(STANDBY_TICK_RATE);
+            } else {
+                asr::print_message("Memory reads recovered, leaving standby");
+                asr::set_tick_rate(DEFAULT_TICK_RATE);
+            }
+        }
+
+        self.active
     }
 }
 
 #[derive(Gui)]
 struct Settings {
-    #[default = false]
-    /// -------- START OPTIONS --------
-    _start: bool,
+    /// Game Options
+    #[heading_level = 0]
+    _game: Title,
+    /// Game executable
+    executable: Executable,
+    #[default = true]
+    /// Poll less often while the game doesn't respond (e.g. when minimized)
+    standby_on_stall: bool,
+    /// Start Options
+    #[heading_level = 0]
+    _start: Title,
     #[default = true]
     /// Enable auto start
     start: bool,
     #[default = false]
-    /// -------- SPLIT OPTIONS: ALL-CUPS & GP MODE --------
-    _split_single: bool,
+    /// Start when the race begins instead of on character confirmation
+    start_on_race_active: bool,
+    #[default = false]
+    /// Start if the game is already at the start gate when the splitter attaches to it
+    start_if_already_at_gate: bool,
+    #[default = false]
+    /// Start with a single start indicator if the other one can't be read
+    run_start_single_indicator: bool,
+    #[default = false]
+    /// World Tour: start on any save file, even if events have already been completed
+    wt_start_ignore_stars: bool,
+    /// World Tour: events that need to be uncompleted for the save file to count as fresh
+    wt_start_events: WorldTourStartEvents,
+    #[default = false]
+    /// World Tour: start in any world (for individual world runs)
+    wt_start_any_world: bool,
+    #[default = true]
+    /// Only if no event of the world has been completed yet
+    wt_start_only_unstarted_world: bool,
+    /// Reset Options
+    #[heading_level = 0]
+    _reset: Title,
+    #[default = false]
+    /// Enable auto reset when quitting a race from the pause menu
+    reset: bool,
+    #[default = false]
+    /// Also reset when returning to the menus after the credits of a completed run
+    reset_after_credits: bool,
+    /// Don't reset during the first moments after the timer starts
+    reset_grace_ms: ResetGracePeriod,
+    /// Timing Options
+    #[heading_level = 0]
+    _timing: Title,
+    #[default = true]
+    /// Use the in-game time as game time (when disabled, game time runs in real time while racing)
+    override_game_time: bool,
+    #[default = false]
+    /// Pause game time while the in-game timer stalls during a race (e.g. Steam overlay), when not using the in-game time
+    pause_on_igt_stall: bool,
+    #[default = false]
+    /// Count the time spent on the results screen (added to the in-game time when using it)
+    count_results_time: bool,
+    #[default = false]
+    /// Single Race: use the final race time as game time once the race is completed
+    single_race_use_final_time: bool,
+    #[default = false]
+    /// Don't count the time spent on races you retried from the pause menu
+    discard_retried_time: bool,
+    #[default = false]
+    /// Grand Prix: only count the final time of each completed race
+    gp_clean_race_time: bool,
+    #[default = false]
+    /// Count the race already on screen when the splitter attaches during a running timer
+    sync_on_midrun_attach: bool,
+    /// World Tour: offset applied to the final game time of a run ending on the credits
+    credits_split_offset_ms: CreditsSplitOffset,
+    /// Splits happening as game time gets paused or resumed
+    split_timing: SplitTiming,
+    /// Split Options: All-Cups & GP Mode
+    #[heading_level = 0]
+    _split_single: Title,
+    #[default = false]
+    /// Split on the results screen instead of when the race is completed
+    split_on_results_screen: bool,
+    #[default = false]
+    /// Grand Prix: split once per cup when the cup results appear, instead of on each race
+    gp_split_on_cup_results: bool,
+    #[default = false]
+    /// Marathon: only split on the first completion of each enabled track, ending the run once every track is completed
+    gp_marathon: bool,
+    #[default = false]
+    /// Grand Prix: end the run once the cup results of the Bonus Cup appear
+    gp_end_detection: bool,
     #[default = true]
     /// Ocean View
     ocean_view: bool,
@@ -162,9 +531,30 @@ struct Settings {
     #[default = true]
     /// Outrun Bay
     outrun_bay: bool,
+    /// Split Options: World Tour
+    #[heading_level = 0]
+    _world_tour: Title,
     #[default = false]
-    /// -------- SPLIT OPTIONS: WORLD TOUR --------
-    _world_tour: bool,
+    /// 100% mode: end the run once every event has been completed with the maximum star rank
+    wt_hundred_percent: bool,
+    /// Run end: what ends a World Tour run
+    wt_end_source: WorldTourEndSource,
+    /// Star target: end the run once the overall star total reaches it (for "X stars" categories)
+    wt_target_stars: TargetStars,
+    /// World splits: also split on the transitions between worlds
+    wt_world_split: WorldTourWorldSplit,
+    /// Failed star reads (Hold Last avoids false splits on a glitched read, Clear to Zero
+    /// avoids getting stuck on stale values)
+    star_read_failure_policy: StarReadFailurePolicy,
+    /// Route: only split on the events of the selected route, in order
+    wt_route: WorldTourRoute,
+    /// Race of AGES (Moonlight Park, end of Any%): split behavior
+    arcade_split_mode: FinalEventSplitMode,
+    /// Race of AGES (Superstar Showdown, end of 100%): split behavior
+    finale_split_mode: FinalEventSplitMode,
+    /// Sunshine Coast
+    #[heading_level = 1]
+    _sunshine_coast: Title,
     #[default = true]
     /// Coastal Cruise
     coastal_cruise: bool,
@@ -189,6 +579,9 @@ struct Settings {
     #[default = true]
     /// Canyon Carnage
     canyon_carnage: bool,
+    /// Frozen Valley
+    #[heading_level = 1]
+    _frozen_valley: Title,
     #[default = true]
     /// Snowball Shakedown
     snowball_shakedown: bool,
@@ -219,6 +612,9 @@ struct Settings {
     #[default = true]
     /// Pirate Plunder
     pirate_plunder: bool,
+    /// Scorching Skies
+    #[heading_level = 1]
+    _scorching_skies: Title,
     #[default = true]
     /// Adder Assault
     adder_assault: bool,
@@ -249,6 +645,9 @@ struct Settings {
     #[default = true]
     /// Hangar Hassle
     hangar_hassle: bool,
+    /// Twilight Engine
+    #[heading_level = 1]
+    _twilight_engine: Title,
     #[default = true]
     /// Booty Boost
     booty_boost: bool,
@@ -279,6 +678,9 @@ struct Settings {
     #[default = true]
     /// Golden Gauntlet
     golden_gauntlet: bool,
+    /// Moonlight Park
+    #[heading_level = 1]
+    _moonlight_park: Title,
     #[default = true]
     /// Carnival Clash
     carnival_clash: bool,
@@ -309,6 +711,9 @@ struct Settings {
     #[default = true]
     /// Arcade Annihilation
     arcade_annihilation: bool,
+    /// Superstar Showdown
+    #[heading_level = 1]
+    _superstar_showdown: Title,
     #[default = true]
     /// Rapid Ruins
     rapid_ruins: bool,
@@ -339,20 +744,370 @@ struct Settings {
     #[default = true]
     /// Fatal Finale
     fatal_finale: bool,
+    /// Debug Options
+    #[heading_level = 0]
+    _debug: Title,
+    #[default = false]
+    /// Show the resolved memory addresses as variables, for reporting broken game versions
+    debug_addresses: bool,
+    #[default = false]
+    /// Publish the state of the splitter as "state:*" variables, for companion tools
+    export_state: bool,
+}
+
+impl Settings {
+    /// Returns whether splitting is enabled for a track in Grand Prix and Single Race
+    const fn track_enabled(&self, track: Tracks) -> bool {
+        match track {
+            Tracks::OceanView => self.ocean_view,
+            Tracks::SambaStudios => self.samba_studios,
+            Tracks::CarrierZone => self.carrier_zone,
+            Tracks::DragonCanyon => self.dragon_canyon,
+            Tracks::TempleTrouble => self.temple_trouble,
+            Tracks::GalacticParade => self.galactic_parade,
+            Tracks::SeasonalShrines => self.seasonal_shrines,
+            Tracks::RoguesLanding => self.rogues_landing,
+            Tracks::DreamValley => self.dream_valley,
+            Tracks::ChillyCastle => self.chilly_castle,
+            Tracks::GraffitiCity => self.graffiti_city,
+            Tracks::SanctuaryFalls => self.sanctuary_falls,
+            Tracks::GraveyardGig => self.graveyard_gig,
+            Tracks::AddersLair => self.adders_lair,
+            Tracks::BurningDepths => self.burning_depths,
+            Tracks::RaceOfAges => self.race_of_ages,
+            Tracks::SushineTour => self.sunshine_tour,
+            Tracks::ShibuyaDowntown => self.shibuya_downtown,
+            Tracks::RouletteRoad => self.roulette_road,
+            Tracks::EggHangar => self.egg_hangar,
+            Tracks::OutrunBay => self.outrun_bay,
+        }
+    }
+
+    /// Returns whether any split can be triggered in the given game mode
+    fn splits_enabled(&self, game_mode: GameMode) -> bool {
+        match game_mode {
+            GameMode::WorldTour => {
+                self.wt_end_source == WorldTourEndSource::CreditsFlag
+                    || self.wt_hundred_percent
+                    || self.wt_target_stars.stars().is_some()
+                    || self.wt_world_split != WorldTourWorldSplit::Off
+                    || self.world_tour_events().contains(&true)
+            }
+            GameMode::GandPrix if self.gp_end_detection => true,
+            GameMode::GandPrix if self.gp_split_on_cup_results => true,
+            GameMode::GandPrix | GameMode::SingleRace => TRACK_HASHES
+                .iter()
+                .any(|&(_, track)| self.track_enabled(track)),
+            GameMode::TimeAttack => false,
+        }
+    }
+
+    /// Returns whether splitting is enabled for every World Tour event, in the order they appear in game
+    fn world_tour_events(&self) -> [bool; WORLD_TOUR_EVENTS] {
+        [
+            self.coastal_cruise,
+            self.studio_scrapes,
+            self.battlezone_blast,
+            self.downtown_drift,
+            self.monkey_mayhem,
+            self.starry_speedway,
+            self.roulette_rush,
+            self.canyon_carnage,
+            self.snowball_shakedown,
+            self.banana_boost,
+            self.shinobi_scramble,
+            self.seaside_scrap,
+            self.tricky_traffic,
+            self.studio_scurry,
+            self.graffiti_groove,
+            self.shaking_skies,
+            self.neon_knockout,
+            self.pirate_plunder,
+            self.adder_assault,
+            self.dreamy_drive,
+            self.sanctuary_speedway,
+            self.keils_carnage,
+            self.carrier_crisis,
+            self.sunshine_slide,
+            self.rogue_rings,
+            self.seaside_skirmish,
+            self.shrine_time,
+            self.hangar_hassle,
+            self.booty_boost,
+            self.racing_rangers,
+            self.shinobi_showdown,
+            self.ruin_run,
+            self.monkey_brawl,
+            self.crumbling_chaos,
+            self.hatcher_hustle,
+            self.death_egg_duel,
+            self.undertaker_overtaker,
+            self.golden_gauntlet,
+            self.carnival_clash,
+            self.curien_curves,
+            self.molten_mayhem,
+            self.speeding_seasons,
+            self.burning_boost,
+            self.ocean_outrun,
+            self.billy_backslide,
+            self.carrier_charge,
+            self.jet_set_jaunt,
+            self.arcade_annihilation,
+            self.rapid_ruins,
+            self.zombie_zoom,
+            self.maracar_madness,
+            self.nightmare_meander,
+            self.maraca_melee,
+            self.castle_chaos,
+            self.volcano_velocity,
+            self.ranger_rush,
+            self.tokyo_takeover,
+            self.fatal_finale,
+        ]
+    }
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+enum StarReadFailurePolicy {
+    /// Hold Last
+    #[default]
+    HoldLast,
+    /// Clear to Zero
+    ClearToZero,
+}
+
+/// What ends a World Tour run with the final split
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+enum WorldTourEndSource {
+    /// Events (100% mode or star target, if enabled)
+    #[default]
+    Events,
+    /// Credits Flag (the first time the credits are reached, whichever event precedes them)
+    CreditsFlag,
+}
+
+/// Transitions between World Tour worlds that trigger a split. Only one of them can be
+/// selected, as both mark the same transitions and would otherwise split twice on them.
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+enum WorldTourWorldSplit {
+    /// Off
+    #[default]
+    Off,
+    /// World Entry (when you clear the first event of each world)
+    WorldEntry,
+    /// World Segment (when moving on to a later world than any reached so far)
+    WorldSegment,
+}
+
+/// Split behavior of the Race of AGES events ending the World Tour categories
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+enum FinalEventSplitMode {
+    /// On Credits (the completion ending the category splits once the credits are reached)
+    #[default]
+    Credits,
+    /// On Max Value (only split when the event reaches 4 stars)
+    MaxValue,
+    /// On Any Increase (split whenever stars are gained)
+    AnyIncrease,
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+enum WorldTourRoute {
+    /// Off
+    #[default]
+    Off,
+    /// Any% (Sunshine Coast to Moonlight Park, in game order)
+    AnyPercent,
+    /// All Events (in game order)
+    AllEvents,
+}
+
+impl WorldTourRoute {
+    /// Returns the indices of the events of the route, in the order they are expected
+    const fn events(self) -> &'static [usize] {
+        match self {
+            Self::Off => &[],
+            Self::AnyPercent => &ROUTE_ANY_PERCENT,
+            Self::AllEvents => &ROUTE_ALL_EVENTS,
+        }
+    }
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+enum WorldTourStartEvents {
+    /// Coastal Cruise and Canyon Carnage
+    #[default]
+    CoastalCruiseAndCanyonCarnage,
+    /// Coastal Cruise
+    CoastalCruise,
+    /// Every Sunshine Coast event
+    SunshineCoast,
+}
+
+impl WorldTourStartEvents {
+    /// Returns the indices of the events whose stars need to be 0 for a run to start.
+    /// Besides the first event, the last event of Sunshine Coast is checked by default, so
+    /// a save file where only the first event reads as uncompleted isn't taken for a new one.
+    const fn events(self) -> &'static [usize] {
+        match self {
+            Self::CoastalCruiseAndCanyonCarnage => &[COASTAL_CRUISE, CANYON_CARNAGE],
+            Self::CoastalCruise => &[COASTAL_CRUISE],
+            Self::SunshineCoast => &START_EVENTS_FIRST_WORLD,
+        }
+    }
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+enum ResetGracePeriod {
+    /// Off
+    Off,
+    /// 1 second
+    OneSecond,
+    /// 3 seconds
+    #[default]
+    ThreeSeconds,
+    /// 5 seconds
+    FiveSeconds,
+    /// 10 seconds
+    TenSeconds,
+}
+
+impl ResetGracePeriod {
+    const fn milliseconds(self) -> u32 {
+        match self {
+            Self::Off => 0,
+            Self::OneSecond => 1_000,
+            Self::ThreeSeconds => 3_000,
+            Self::FiveSeconds => 5_000,
+            Self::TenSeconds => 10_000,
+        }
+    }
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+enum SplitTiming {
+    /// Record the game time after the pause or resume
+    #[default]
+    AfterLoadChange,
+    /// Record the game time before the pause or resume
+    BeforeLoadChange,
+}
+
+impl SplitTiming {
+    /// Runs the change of the loading state and the update of the timer in the order set by
+    /// the setting, so a split coinciding with the change records the game time from before
+    /// or after it
+    fn sequence(self, load_change: impl FnOnce(), timer_update: impl FnOnce()) {
+        match self {
+            Self::AfterLoadChange => {
+                load_change();
+                timer_update();
+            }
+            Self::BeforeLoadChange => {
+                timer_update();
+                load_change();
+            }
+        }
+    }
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+enum CreditsSplitOffset {
+    /// -1 second
+    MinusOneSecond,
+    /// -0.5 seconds
+    MinusHalfSecond,
+    /// Off
+    #[default]
+    Off,
+    /// +0.5 seconds
+    PlusHalfSecond,
+    /// +1 second
+    PlusOneSecond,
+}
+
+impl CreditsSplitOffset {
+    const fn milliseconds(self) -> i64 {
+        match self {
+            Self::MinusOneSecond => -1_000,
+            Self::MinusHalfSecond => -500,
+            Self::Off => 0,
+            Self::PlusHalfSecond => 500,
+            Self::PlusOneSecond => 1_000,
+        }
+    }
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+enum TargetStars {
+    /// Off
+    #[default]
+    Off,
+    /// 50 stars
+    Fifty,
+    /// 100 stars
+    OneHundred,
+    /// 150 stars
+    OneHundredFifty,
+    /// 200 stars
+    TwoHundred,
+}
+
+impl TargetStars {
+    const fn stars(self) -> Option<u32> {
+        match self {
+            Self::Off => None,
+            Self::Fifty => Some(50),
+            Self::OneHundred => Some(100),
+            Self::OneHundredFifty => Some(150),
+            Self::TwoHundred => Some(200),
+        }
+    }
+}
+
+#[derive(Gui, Clone, Copy, PartialEq, Eq)]
+enum Executable {
+    /// Auto-detect (try every known executable)
+    #[default]
+    Auto,
+    /// ASN_App_PcDx9_Final.exe (Steam)
+    Steam,
+    /// ASRT.exe (renamed executable)
+    Asrt,
+    /// SASRT.exe (renamed executable)
+    Sasrt,
+}
+
+impl Executable {
+    /// Returns the executable names to try attaching to, in order
+    const fn process_names(self) -> &'static [&'static str] {
+        match self {
+            Self::Auto => &PROCESS_NAMES,
+            Self::Steam => &[PROCESS_NAMES[0]],
+            Self::Asrt => &[PROCESS_NAMES[1]],
+            Self::Sasrt => &[PROCESS_NAMES[2]],
+        }
+    }
 }
 
 #[derive(Default)]
 struct Watchers {
     run_start: Watcher<bool>,
+    run_start_reads: [bool; 2],
     end_credits: Watcher<bool>,
     game_mode: Watcher<GameMode>,
     required_laps: Watcher<u8>,
+    total_race_time_secs: Option<f32>,
     total_race_time: Watcher<Duration>,
     race_completed: Watcher<bool>,
     race_status: Watcher<u8>,
+    results_screen: Watcher<bool>,
     igt: Watcher<Duration>,
+    race_time_shown: Duration,
+    igt_stalled_ticks: u8,
     event_type: Watcher<u32>,
     track_id: Watcher<Tracks>,
+    input_device: Watcher<InputDevice>,
     total_igt: Duration,
     progress_igt: Duration,
     coastal_cruise: Watcher<u8>,
@@ -413,6 +1168,158 @@ struct Watchers {
     ranger_rush: Watcher<u8>,
     tokyo_takeover: Watcher<u8>,
     fatal_finale: Watcher<u8>,
+    all_stars_collected: Watcher<bool>,
+    total_stars: Watcher<u32>,
+    current_world: Watcher<usize>,
+    star_blocks_read: bool,
+    furthest_world: Option<usize>,
+    world_segment_entered: bool,
+    race_exit: Watcher<RaceExit>,
+    race_in_progress: bool,
+    route_position: usize,
+    completed_tracks: Watcher<u32>,
+    quit_pending_ticks: Option<u8>,
+    quit_confirmed: bool,
+    game_mode_valid: bool,
+    game_mode_failed_ticks: u8,
+    accumulated_for_race: bool,
+    igt_capped: bool,
+    run_ended: bool,
+    credits_igt: Option<Duration>,
+    auto_splits: u32,
+    pending_splits: usize,
+    ticks_since_start: u8,
+    ticks_since_timer_start: u32,
+    ticks_since_attach: u8,
+}
+
+impl Watchers {
+    /// Returns the star watchers of every World Tour event, in the order they appear in game
+    fn world_tour_events(&self) -> [&Watcher<u8>; WORLD_TOUR_EVENTS] {
+        [
+            &self.coastal_cruise,
+            &self.studio_scrapes,
+            &self.battlezone_blast,
+            &self.downtown_drift,
+            &self.monkey_mayhem,
+            &self.starry_speedway,
+            &self.roulette_rush,
+            &self.canyon_carnage,
+            &self.snowball_shakedown,
+            &self.banana_boost,
+            &self.shinobi_scramble,
+            &self.seaside_scrap,
+            &self.tricky_traffic,
+            &self.studio_scurry,
+            &self.graffiti_groove,
+            &self.shaking_skies,
+            &self.neon_knockout,
+            &self.pirate_plunder,
+            &self.adder_assault,
+            &self.dreamy_drive,
+            &self.sanctuary_speedway,
+            &self.keils_carnage,
+            &self.carrier_crisis,
+            &self.sunshine_slide,
+            &self.rogue_rings,
+            &self.seaside_skirmish,
+            &self.shrine_time,
+            &self.hangar_hassle,
+            &self.booty_boost,
+            &self.racing_rangers,
+            &self.shinobi_showdown,
+            &self.ruin_run,
+            &self.monkey_brawl,
+            &self.crumbling_chaos,
+            &self.hatcher_hustle,
+            &self.death_egg_duel,
+            &self.undertaker_overtaker,
+            &self.golden_gauntlet,
+            &self.carnival_clash,
+            &self.curien_curves,
+            &self.molten_mayhem,
+            &self.speeding_seasons,
+            &self.burning_boost,
+            &self.ocean_outrun,
+            &self.billy_backslide,
+            &self.carrier_charge,
+            &self.jet_set_jaunt,
+            &self.arcade_annihilation,
+            &self.rapid_ruins,
+            &self.zombie_zoom,
+            &self.maracar_madness,
+            &self.nightmare_meander,
+            &self.maraca_melee,
+            &self.castle_chaos,
+            &self.volcano_velocity,
+            &self.ranger_rush,
+            &self.tokyo_takeover,
+            &self.fatal_finale,
+        ]
+    }
+
+    fn world_tour_events_mut(&mut self) -> [&mut Watcher<u8>; WORLD_TOUR_EVENTS] {
+        [
+            &mut self.coastal_cruise,
+            &mut self.studio_scrapes,
+            &mut self.battlezone_blast,
+            &mut self.downtown_drift,
+            &mut self.monkey_mayhem,
+            &mut self.starry_speedway,
+            &mut self.roulette_rush,
+            &mut self.canyon_carnage,
+            &mut self.snowball_shakedown,
+            &mut self.banana_boost,
+            &mut self.shinobi_scramble,
+            &mut self.seaside_scrap,
+            &mut self.tricky_traffic,
+            &mut self.studio_scurry,
+            &mut self.graffiti_groove,
+            &mut self.shaking_skies,
+            &mut self.neon_knockout,
+            &mut self.pirate_plunder,
+            &mut self.adder_assault,
+            &mut self.dreamy_drive,
+            &mut self.sanctuary_speedway,
+            &mut self.keils_carnage,
+            &mut self.carrier_crisis,
+            &mut self.sunshine_slide,
+            &mut self.rogue_rings,
+            &mut self.seaside_skirmish,
+            &mut self.shrine_time,
+            &mut self.hangar_hassle,
+            &mut self.booty_boost,
+            &mut self.racing_rangers,
+            &mut self.shinobi_showdown,
+            &mut self.ruin_run,
+            &mut self.monkey_brawl,
+            &mut self.crumbling_chaos,
+            &mut self.hatcher_hustle,
+            &mut self.death_egg_duel,
+            &mut self.undertaker_overtaker,
+            &mut self.golden_gauntlet,
+            &mut self.carnival_clash,
+            &mut self.curien_curves,
+            &mut self.molten_mayhem,
+            &mut self.speeding_seasons,
+            &mut self.burning_boost,
+            &mut self.ocean_outrun,
+            &mut self.billy_backslide,
+            &mut self.carrier_charge,
+            &mut self.jet_set_jaunt,
+            &mut self.arcade_annihilation,
+            &mut self.rapid_ruins,
+            &mut self.zombie_zoom,
+            &mut self.maracar_madness,
+            &mut self.nightmare_meander,
+            &mut self.maraca_melee,
+            &mut self.castle_chaos,
+            &mut self.volcano_velocity,
+            &mut self.ranger_rush,
+            &mut self.tokyo_takeover,
+            &mut self.fatal_finale,
+        ]
+    }
 }
 
 struct Addresses {
@@ -426,190 +1333,421 @@ struct Addresses {
     igt: Address,
     event_type: Address,
     sunshine_coast: Address,
+    input_device: Address,
+    build: Build,
+    scan_ranges: ScanRanges,
+}
+
+/// Identifies the build of the game, so users can easily report which one they are on
+/// and version-specific signatures can be selected
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Build {
+    timestamp: u32,
+    image_size: u64,
+}
+
+impl Build {
+    fn format(self) -> VariableBuffer<32> {
+        let mut buf = VariableBuffer::new();
+        let _ = write!(buf, "{:08X}-{:X}", self.timestamp, self.image_size);
+        buf
+    }
 }
 
 impl Addresses {
-    fn init(game: &Process) -> Option<Self> {
-        let main_module_base = game.get_module_address(PROCESS_NAME).ok()?;
-        let main_module_size = pe::read_size_of_image(game, main_module_base)? as _;
-        let main_module = (main_module_base, main_module_size);
+    /// Publishes every resolved address as a hexadecimal variable
+    fn publish_variables(&self) {
+        for id in AddressId::ALL {
+            let mut name = VariableBuffer::<32>::new();
+            let _ = write!(name, "addr:{}", id.name());
+            let mut buf = VariableBuffer::<20>::new();
+            let _ = write!(buf, "0x{:X}", self.get(id).value());
+            timer::set_variable(name.as_str(), buf.as_str());
+        }
+    }
+
+    fn init(game: &Process, process_name: &str) -> Option<Self> {
+        let main_module_base = game.get_module_address(process_name).ok()?;
 
         // Check if the hooked process is 32-bit before continuing
         if pe::MachineType::read(game, main_module_base)? != pe::MachineType::X86 {
             return None;
         }
 
-        let run_start = {
-            const SIG: Signature<14> = Signature::new("80 3D ?? ?? ?? ?? 00 0F 85 ?? ?? ?? ?? 56");
-            let ptr = SIG.scan_process_range(game, main_module)? + 2;
-            game.read::<Address32>(ptr).ok()?.into()
-        };
+        let header_size = pe::read_size_of_image(game, main_module_base).map(|size| size as u64);
+        let runtime_size = game.get_module_size(process_name).ok();
+        if !header_size.is_some_and(|size| X86_MODULE_SIZES.contains(&size))
+            && runtime_size.is_some_and(|size| !X86_MODULE_SIZES.contains(&size))
+        {
+            asr::print_message(
+                "The module size doesn't fit a 32-bit image, scanning a default window",
+            );
+        }
+        let main_module_size = main_module_size(header_size, runtime_size);
+        let main_module = (main_module_base, main_module_size);
 
-        let run_start_2 = {
-            const SIG: Signature<11> = Signature::new("74 0E 83 3D ?? ?? ?? ?? 00 74 0E");
-            let ptr = SIG.scan_process_range(game, main_module)? + 4;
-            game.read::<Address32>(ptr).ok()?.into()
+        let build = Build {
+            timestamp: pe_timestamp(game, main_module_base)?,
+            image_size: main_module_size,
         };
 
-        let end_credits = {
-            const SIG: Signature<3> = Signature::new("7E 5C A1");
-            let ptr = SIG.scan_process_range(game, main_module)? + 3;
-            game.read::<Address32>(ptr).ok()?.into()
+        // All the signatures match instructions referencing the variables we need,
+        // so we can restrict the scans to the code section in order to avoid false matches
+        let scan_ranges = ScanRanges {
+            code_section: pe_section(game, main_module_base, b".text"),
+            main_module,
         };
-
-        let mode_select = {
-            const SIG: Signature<10> = Signature::new("A1 ?? ?? ?? ?? 83 F8 02 74 16");
-            let ptr = SIG.scan_process_range(game, main_module)? + 1;
-            game.read::<Address32>(ptr).ok()?.into()
+        let mut addresses = Self {
+            run_start: Address::NULL,
+            run_start_2: Address::NULL,
+            end_credits: Address::NULL,
+            mode_select: Address::NULL,
+            player_base: Address::NULL,
+            race_completed: Address::NULL,
+            race_status: Address::NULL,
+            igt: Address::NULL,
+            event_type: Address::NULL,
+            sunshine_coast: Address::NULL,
+            input_device: Address::NULL,
+            build,
+            scan_ranges,
         };
 
-        let player_base = {
-            const SIG: Signature<13> = Signature::new("A1 ?? ?? ?? ?? 85 C0 0F 84 8D 00 00 00");
-            let ptr = SIG.scan_process_range(game, main_module)? + 1;
-            game.read::<Address32>(ptr).ok()?.into()
-        };
+        for id in AddressId::ALL {
+            if !addresses.resolve(game, id) {
+                return None;
+            }
+        }
+        for id in AddressId::OPTIONAL {
+            addresses.resolve(game, id);
+        }
 
-        let race_completed = {
-            const SIG: Signature<11> = Signature::new("8B 04 24 A3 ?? ?? ?? ?? 83 C4 08");
-            let ptr = SIG.scan_process_range(game, main_module)? + 4;
-            game.read::<Address32>(ptr).ok()?.into()
-        };
+        Some(addresses)
+    }
 
-        let race_status = {
-            const SIG: Signature<11> = Signature::new("7C 44 83 3D ?? ?? ?? ?? 00 74 3B");
-            let ptr = SIG.scan_process_range(game, main_module)? + 4;
-            game.read::<Address32>(ptr).ok()?.into()
+    /// Resolves a single address again through its signature, e.g. once it stopped being
+    /// readable while the others still are. Returns whether the address could be resolved.
+    fn resolve(&mut self, game: &Process, id: AddressId) -> bool {
+        let Some(address) = id.resolve(game, self.scan_ranges) else {
+            return false;
         };
+        *self.get_mut(id) = address;
+        true
+    }
 
-        let igt = {
-            const SIG: Signature<7> = Signature::new("D8 05 ?? ?? ?? ?? 56");
-            let ptr = SIG.scan_process_range(game, main_module)? + 2;
-            game.read::<Address32>(ptr).ok()?.into()
-        };
+    const fn get(&self, id: AddressId) -> Address {
+        match id {
+            AddressId::RunStart => self.run_start,
+            AddressId::RunStart2 => self.run_start_2,
+            AddressId::EndCredits => self.end_credits,
+            AddressId::ModeSelect => self.mode_select,
+            AddressId::PlayerBase => self.player_base,
+            AddressId::RaceCompleted => self.race_completed,
+            AddressId::RaceStatus => self.race_status,
+            AddressId::Igt => self.igt,
+            AddressId::EventType => self.event_type,
+            AddressId::SunshineCoast => self.sunshine_coast,
+            AddressId::InputDevice => self.input_device,
+        }
+    }
 
-        let event_type = {
-            const SIG: Signature<10> = Signature::new("55 8B E9 8B 0D ?? ?? ?? ?? 57");
-            let ptr = SIG.scan_process_range(game, main_module)? + 5;
-            game.read::<Address32>(ptr).ok()?.into()
-        };
+    fn get_mut(&mut self, id: AddressId) -> &mut Address {
+        match id {
+            AddressId::RunStart => &mut self.run_start,
+            AddressId::RunStart2 => &mut self.run_start_2,
+            AddressId::EndCredits => &mut self.end_credits,
+            AddressId::ModeSelect => &mut self.mode_select,
+            AddressId::PlayerBase => &mut self.player_base,
+            AddressId::RaceCompleted => &mut self.race_completed,
+            AddressId::RaceStatus => &mut self.race_status,
+            AddressId::Igt => &mut self.igt,
+            AddressId::EventType => &mut self.event_type,
+            AddressId::SunshineCoast => &mut self.sunshine_coast,
+            AddressId::InputDevice => &mut self.input_device,
+        }
+    }
+}
 
-        let sunshine_coast = {
-            const SIG: Signature<11> = Signature::new("8B 2C 85 ?? ?? ?? ?? 89 7C 24 20");
-            let ptr = SIG.scan_process_range(game, main_module)? + 3;
-            game.read::<Address32>(ptr).ok()?.into()
-        };
+/// Ranges of the main module the signatures are scanned in
+#[derive(Clone, Copy)]
+struct ScanRanges {
+    code_section: Option<(Address, u64)>,
+    main_module: (Address, u64),
+}
 
-        Some(Self {
-            run_start,
-            run_start_2,
-            end_credits,
-            mode_select,
-            player_base,
-            race_completed,
-            race_status,
-            igt,
-            event_type,
-            sunshine_coast,
-        })
+/// Identifies each of the addresses resolved through a signature scan
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum AddressId {
+    RunStart,
+    RunStart2,
+    EndCredits,
+    ModeSelect,
+    PlayerBase,
+    RaceCompleted,
+    RaceStatus,
+    Igt,
+    EventType,
+    SunshineCoast,
+    InputDevice,
+}
+
+impl AddressId {
+    const ALL: [Self; 10] = [
+        Self::RunStart,
+        Self::RunStart2,
+        Self::EndCredits,
+        Self::ModeSelect,
+        Self::PlayerBase,
+        Self::RaceCompleted,
+        Self::RaceStatus,
+        Self::Igt,
+        Self::EventType,
+        Self::SunshineCoast,
+    ];
+
+    /// Addresses that aren't needed for the splitter to work, so failing to resolve them
+    /// doesn't prevent attaching to the game
+    const OPTIONAL: [Self; 1] = [Self::InputDevice];
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::RunStart => "run_start",
+            Self::RunStart2 => "run_start_2",
+            Self::EndCredits => "end_credits",
+            Self::ModeSelect => "mode_select",
+            Self::PlayerBase => "player_base",
+            Self::RaceCompleted => "race_completed",
+            Self::RaceStatus => "race_status",
+            Self::Igt => "igt",
+            Self::EventType => "event_type",
+            Self::SunshineCoast => "sunshine_coast",
+            Self::InputDevice => "input_device",
+        }
+    }
+
+    fn resolve(self, game: &Process, ranges: ScanRanges) -> Option<Address> {
+        let ptr: Address = match self {
+            Self::RunStart => {
+                const SIG: Signature<14> =
+                    Signature::new("80 3D ?? ?? ?? ?? 00 0F 85 ?? ?? ?? ?? 56");
+                scan_section(game, &SIG, ranges.code_section, ranges.main_module)? + 2
+            }
+            Self::RunStart2 => {
+                const SIG: Signature<11> = Signature::new("74 0E 83 3D ?? ?? ?? ?? 00 74 0E");
+                scan_section(game, &SIG, ranges.code_section, ranges.main_module)? + 4
+            }
+            Self::EndCredits => {
+                const SIG: Signature<3> = Signature::new("7E 5C A1");
+                scan_section(game, &SIG, ranges.code_section, ranges.main_module)? + 3
+            }
+            Self::ModeSelect => {
+                const SIG: Signature<10> = Signature::new("A1 ?? ?? ?? ?? 83 F8 02 74 16");
+                scan_section(game, &SIG, ranges.code_section, ranges.main_module)? + 1
+            }
+            Self::PlayerBase => {
+                const SIG: Signature<13> = Signature::new("A1 ?? ?? ?? ?? 85 C0 0F 84 8D 00 00 00");
+                scan_section(game, &SIG, ranges.code_section, ranges.main_module)? + 1
+            }
+            Self::RaceCompleted => {
+                const SIG: Signature<11> = Signature::new("8B 04 24 A3 ?? ?? ?? ?? 83 C4 08");
+                scan_section(game, &SIG, ranges.code_section, ranges.main_module)? + 4
+            }
+            Self::RaceStatus => {
+                const SIG: Signature<11> = Signature::new("7C 44 83 3D ?? ?? ?? ?? 00 74 3B");
+                scan_section(game, &SIG, ranges.code_section, ranges.main_module)? + 4
+            }
+            Self::Igt => {
+                const SIG: Signature<7> = Signature::new("D8 05 ?? ?? ?? ?? 56");
+                scan_section(game, &SIG, ranges.code_section, ranges.main_module)? + 2
+            }
+            Self::EventType => {
+                const SIG: Signature<10> = Signature::new("55 8B E9 8B 0D ?? ?? ?? ?? 57");
+                scan_section(game, &SIG, ranges.code_section, ranges.main_module)? + 5
+            }
+            Self::SunshineCoast => {
+                const SIG: Signature<11> = Signature::new("8B 2C 85 ?? ?? ?? ?? 89 7C 24 20");
+                scan_section(game, &SIG, ranges.code_section, ranges.main_module)? + 3
+            }
+            // No memory value holding the active input device has been located yet, so there's
+            // no signature to scan for. Adding one here is all it takes to enable the detection.
+            Self::InputDevice => return None,
+        };
+        Some(game.read::<Address32>(ptr).ok()?.into())
     }
 }
 
-fn update_loop(game: &Process, addresses: &Addresses, watchers: &mut Watchers) {
-    watchers.run_start.update_infallible(
-        game.read::<u8>(addresses.run_start)
-            .is_ok_and(|value| value == 1)
-            && game
-                .read::<u8>(addresses.run_start_2)
-                .is_ok_and(|value| value == 1),
-    );
+/// Reads the link timestamp from the file header of a PE module
+fn pe_timestamp(game: &Process, module_base: Address) -> Option<u32> {
+    let nt_headers = module_base + game.read::<u32>(module_base + 0x3C).ok()? as u64;
+    game.read::<u32>(nt_headers + 0x8).ok()
+}
+
+/// Looks up the address range of a section of a PE module by its name
+fn pe_section(game: &Process, module_base: Address, name: &[u8]) -> Option<(Address, u64)> {
+    let nt_headers = module_base + game.read::<u32>(module_base + 0x3C).ok()? as u64;
+    let number_of_sections = game.read::<u16>(nt_headers + 0x6).ok()?;
+    let size_of_optional_header = game.read::<u16>(nt_headers + 0x14).ok()?;
+    let section_table = nt_headers + 0x18 + size_of_optional_header as u64;
+
+    (0..number_of_sections as u64).find_map(|index| {
+        let section = game.read::<[u8; 0x28]>(section_table + index * 0x28).ok()?;
+        let section_name = section[..8].split(|&byte| byte == 0).next()?;
+        if section_name != name {
+            return None;
+        }
+
+        let virtual_size = u32::from_le_bytes(section[0x8..0xC].try_into().ok()?);
+        let virtual_address = u32::from_le_bytes(section[0xC..0x10].try_into().ok()?);
+        Some((module_base + virtual_address as u64, virtual_size as u64))
+    })
+}
+
+/// Picks the size of the main module's image to scan. Some packed executables report a bogus
+/// image size in their headers, in which case the size reported by the runtime is used
+/// instead, or a default window if neither fits a 32-bit image.
+fn main_module_size(header_size: Option<u64>, runtime_size: Option<u64>) -> u64 {
+    [header_size, runtime_size]
+        .into_iter()
+        .flatten()
+        .find(|size| X86_MODULE_SIZES.contains(size))
+        .unwrap_or(DEFAULT_MODULE_SIZE)
+}
+
+/// Scans the given section of the main module for a signature, falling back to the whole
+/// module image if the section couldn't be found or the signature doesn't match in it
+fn scan_section<const N: usize>(
+    game: &Process,
+    signature: &Signature<N>,
+    section: Option<(Address, u64)>,
+    main_module: (Address, u64),
+) -> Option<Address> {
+    section
+        .and_then(|section| signature.scan_process_range(game, section))
+        .or_else(|| signature.scan_process_range(game, main_module))
+}
+
+fn update_loop(
+    game: &Process,
+    addresses: &Addresses,
+    watchers: &mut Watchers,
+    settings: &Settings,
+) {
+    watchers.ticks_since_attach = watchers.ticks_since_attach.saturating_add(1);
+
+    // Both start indicators need to be set. If one of their addresses broke (e.g. after a
+    // patch), the other one can optionally be used on its own instead.
+    let run_start = game.read::<u8>(addresses.run_start);
+    let run_start_2 = game.read::<u8>(addresses.run_start_2);
+    watchers.run_start_reads = [run_start.is_ok(), run_start_2.is_ok()];
+    watchers
+        .run_start
+        .update_infallible(match (run_start, run_start_2) {
+            (Ok(run_start), Ok(run_start_2)) => run_start == 1 && run_start_2 == 1,
+            (Ok(value), Err(_)) | (Err(_), Ok(value)) if settings.run_start_single_indicator => {
+                value == 1
+            }
+            _ => false,
+        });
 
     watchers.end_credits.update_infallible(
         game.read_pointer_path32(addresses.end_credits, &[0, 0x8C])
             .unwrap_or_default(),
     );
 
+    // The game mode is considered stale once its read has been failing for a while, e.g.
+    // after a module reload, in which case the last known mode shouldn't be acted upon
+    let mode_select = game.read::<u8>(addresses.mode_select);
+    if matches!(mode_select, Ok(0..=3)) {
+        watchers.game_mode_failed_ticks = 0;
+    } else {
+        watchers.game_mode_failed_ticks = watchers.game_mode_failed_ticks.saturating_add(1);
+    }
+    watchers.game_mode_valid = watchers.game_mode_failed_ticks < GAME_MODE_STALE_TICKS;
+
+    watchers.game_mode.update_infallible(match mode_select {
+        Ok(0) => GameMode::WorldTour,
+        Ok(1) => GameMode::GandPrix,
+        Ok(2) => GameMode::TimeAttack,
+        Ok(3) => GameMode::SingleRace,
+        _ => {
+            watchers
+                .game_mode
+                .pair
+                .unwrap_or(Pair {
+                    current: GameMode::WorldTour,
+                    old: GameMode::WorldTour,
+                })
+                .current
+        }
+    });
+
     watchers
-        .game_mode
-        .update_infallible(match game.read::<u8>(addresses.mode_select) {
-            Ok(0) => GameMode::WorldTour,
-            Ok(1) => GameMode::GandPrix,
-            Ok(2) => GameMode::TimeAttack,
-            Ok(3) => GameMode::SingleRace,
-            _ => {
-                watchers
-                    .game_mode
-                    .pair
-                    .unwrap_or(Pair {
-                        current: GameMode::WorldTour,
-                        old: GameMode::WorldTour,
-                    })
-                    .current
-            }
-        });
+        .race_completed
+        .update_infallible(game.read(addresses.race_completed).unwrap_or_default());
+    watchers
+        .race_status
+        .update_infallible(game.read(addresses.race_status).unwrap_or_default());
+
+    // The number of required laps is latched when the race starts, so read glitches
+    // during the race can't alter it. It is only updated again between races.
+    let mid_race = watchers.race_status.pair.is_some_and(|value| {
+        value.old == RACE_STATUS_RACING && value.current == RACE_STATUS_RACING
+    });
 
-    let (required_laps, total_race_time) = {
+    let (required_laps, total_race_time_secs) = {
         let mut required_laps: u8 = watchers.required_laps.pair.unwrap_or_default().current;
-        let mut total_race_time = watchers.total_race_time.pair.unwrap_or_default().current;
+        let mut total_race_time_secs = None;
 
         if let Ok(addr) = game.read::<Address32>(addresses.player_base) {
             if let Ok(addr) = game.read::<Address32>(addr + 0) {
                 if let Ok(addr) = game.read::<Address32>(addr + 0xC1B8) {
-                    if let Ok(r_l) = game.read(addr + 0x4) {
-                        required_laps = r_l;
-                    }
-                    if let Ok(t_r_t) = game.read::<f32>(addr + 0x28) {
-                        total_race_time = Duration::milliseconds((t_r_t * 100.0) as i64 * 10);
+                    if !mid_race {
+                        if let Ok(r_l) = game.read(addr + 0x4) {
+                            required_laps = r_l;
+                        }
                     }
+                    total_race_time_secs = game.read::<f32>(addr + 0x28).ok();
                 }
             }
         }
-        (required_laps, total_race_time)
+        (required_laps, total_race_time_secs)
     };
     watchers.required_laps.update_infallible(required_laps);
-    watchers.total_race_time.update_infallible(total_race_time);
+    watchers.total_race_time_secs = total_race_time_secs;
 
-    watchers
-        .race_completed
-        .update_infallible(game.read(addresses.race_completed).unwrap_or_default());
-    watchers
-        .race_status
-        .update_infallible(game.read(addresses.race_status).unwrap_or_default());
     watchers.igt.update_infallible({
-        if let Ok(time) = game.read::<f32>(addresses.igt) {
-            Duration::milliseconds((time * 100.0) as i64 * 10)
+        if let Some(time) = game
+            .read::<f32>(addresses.igt)
+            .ok()
+            .and_then(race_time_from_secs)
+        {
+            time
         } else {
             watchers.igt.pair.unwrap_or_default().current
         }
     });
 
-    watchers.event_type.update_infallible(
+    watchers.event_type.update_infallible(event_type_or_last(
         game.read_pointer_path32(addresses.event_type, &[0x0, 0x0])
-            .unwrap_or_default(),
-    );
+            .ok(),
+        watchers.event_type.pair,
+    ));
+    // The input device is only read once its address is known, keeping the last valid
+    // value whenever a read fails or yields an unknown device
+    if !addresses.input_device.is_null() {
+        if let Some(input_device) = game
+            .read::<u8>(addresses.input_device)
+            .ok()
+            .and_then(InputDevice::from_raw)
+        {
+            watchers.input_device.update_infallible(input_device);
+        }
+    }
     watchers.track_id.update_infallible(
-        match game.read_pointer_path32::<u32>(addresses.event_type + 0x4, &[0x0, 0x0]) {
-            Ok(0xD4257EBD) => Tracks::OceanView,
-            Ok(0x32D305A8) => Tracks::SambaStudios,
-            Ok(0xC72B3B98) => Tracks::CarrierZone,
-            Ok(0x03EB7FFF) => Tracks::DragonCanyon,
-            Ok(0xE3121777) => Tracks::TempleTrouble,
-            Ok(0x4E015AB6) => Tracks::GalacticParade,
-            Ok(0x503C1CBC) => Tracks::SeasonalShrines,
-            Ok(0x7534B7CA) => Tracks::RoguesLanding,
-            Ok(0x38A394ED) => Tracks::DreamValley,
-            Ok(0xC5C9DEA1) => Tracks::ChillyCastle,
-            Ok(0xD936550C) => Tracks::GraffitiCity,
-            Ok(0x4A0FF7AE) => Tracks::SanctuaryFalls,
-            Ok(0xCD8017BA) => Tracks::GraveyardGig,
-            Ok(0xDC93F18B) => Tracks::AddersLair,
-            Ok(0x2DB91FC2) => Tracks::BurningDepths,
-            Ok(0x94610644) => Tracks::RaceOfAges,
-            Ok(0xE6CD97F0) => Tracks::SushineTour,
-            Ok(0xE87FDF22) => Tracks::ShibuyaDowntown,
-            Ok(0x17463C8D) => Tracks::RouletteRoad,
-            Ok(0xFEBC639E) => Tracks::EggHangar,
-            Ok(0x1EF56CE1) => Tracks::OutrunBay,
-            _ => {
+        game.read_pointer_path32::<u32>(addresses.event_type + 0x4, &[0x0, 0x0])
+            .ok()
+            .and_then(Tracks::from_hash)
+            .unwrap_or_else(|| {
                 watchers
                     .track_id
                     .pair
@@ -618,147 +1756,670 @@ fn update_loop(game: &Process, addresses: &Addresses, watchers: &mut Watchers) {
                         current: Tracks::OceanView,
                     })
                     .current
+            }),
+    );
+
+    // The star blocks of each world are pointed to by consecutive pointers, starting
+    // at Sunshine Coast's. A failed read of a star block is handled according to the settings.
+    // The world bases are often null while navigating the menus. In that case, none of the
+    // star blocks can be trusted, so every star watcher retains its value for this tick.
+    // A world base may also point to memory that has been freed and reused, so a star block
+    // holding values out of the range of star ranks is skipped for this tick as well.
+    let world_bases = game
+        .read::<[Address32; WORLD_TOUR_WORLDS.len()]>(addresses.sunshine_coast)
+        .ok()
+        .filter(|world_bases| !world_bases.iter().any(|world_base| world_base.is_null()));
+
+    let star_blocks: [Option<[u8; 0x719]>; WORLD_TOUR_WORLDS.len()] =
+        core::array::from_fn(|world| {
+            world_bases.and_then(|world_bases| game.read(world_bases[world]).ok())
+        });
+
+    let star_blocks_valid: [bool; WORLD_TOUR_WORLDS.len()] = core::array::from_fn(|world| {
+        star_blocks[world].is_some_and(|stars| star_block_valid(&stars, world))
+    });
+
+    for (event, watcher) in watchers.world_tour_events_mut().into_iter().enumerate() {
+        let world = world_of_event(event);
+        let value = match star_blocks[world] {
+            Some(_) if !star_blocks_valid[world] => watcher.pair.unwrap_or_default().current,
+            Some(stars) => stars[STAR_OFFSETS[event - world_events(world).start]],
+            None if world_bases.is_none() => watcher.pair.unwrap_or_default().current,
+            None => match settings.star_read_failure_policy {
+                StarReadFailurePolicy::HoldLast => watcher.pair.unwrap_or_default().current,
+                StarReadFailurePolicy::ClearToZero => 0,
+            },
+        };
+        watcher.update_infallible(value);
+    }
+    watchers.star_blocks_read |= star_blocks_valid.iter().any(|&valid| valid);
+
+    update_state(watchers, settings, timer::state());
+}
+
+/// Updates the state derived from the values read from the game, given the state of the timer
+fn update_state(watchers: &mut Watchers, settings: &Settings, timer_state: TimerState) {
+    // A total race time that fails to read or isn't a valid time keeps its last value
+    let total_race_time = watchers
+        .total_race_time_secs
+        .and_then(race_time_from_secs)
+        .unwrap_or_else(|| watchers.total_race_time.pair.unwrap_or_default().current);
+    watchers.total_race_time.update_infallible(total_race_time);
+
+    // The results screen is shown once the race has been completed and the race status
+    // has moved away from the racing state
+    watchers.results_screen.update_infallible(
+        watchers
+            .race_completed
+            .pair
+            .is_some_and(|value| value.current)
+            && watchers
+                .race_status
+                .pair
+                .is_some_and(|value| value.current != RACE_STATUS_RACING),
+    );
+
+    // The in-game timer stops while the game is paused externally (e.g. by the Steam overlay)
+    // or from its own pause menu, which can't be told apart. Only a stall lasting a few ticks
+    // during a race counts, so a single repeated read doesn't.
+    let racing = watchers
+        .race_status
+        .pair
+        .is_some_and(|value| value.current == RACE_STATUS_RACING);
+    watchers.igt_stalled_ticks = match watchers.igt.pair {
+        Some(igt) if racing && igt.unchanged() => watchers.igt_stalled_ticks.saturating_add(1),
+        _ => 0,
+    };
+
+    // The race time shown to the player never goes backward within a race, so a glitched read
+    // can't make it jitter. It only starts over once the race timer is reset or a race loads.
+    let igt = watchers.igt.pair.unwrap_or_default().current;
+    watchers.race_time_shown = if igt == Duration::ZERO
+        || igt > watchers.race_time_shown
+        || watchers
+            .run_start
+            .pair
+            .is_some_and(|value| value.changed_to(&true))
+    {
+        igt
+    } else {
+        watchers.race_time_shown
+    };
+
+    let required_laps = watchers.required_laps.pair.unwrap_or_default().current;
+
+    // There's no known memory location for the world the player is currently in, so it is
+    // inferred from the star blocks: it's the world of the last event whose stars increased,
+    // or the world holding the first uncompleted event if none have changed yet.
+    // Until a star block has been read successfully, the star watchers only hold placeholder
+    // values, so the world is left unknown rather than being latched to the first one.
+    if watchers.star_blocks_read {
+        let events = watchers.world_tour_events();
+        let current_world = events
+            .iter()
+            .position(|event| event.pair.is_some_and(|value| value.increased()))
+            .map(world_of_event)
+            .or_else(|| watchers.current_world.pair.map(|value| value.current))
+            .unwrap_or_else(|| {
+                events
+                    .iter()
+                    .position(|event| event.pair.is_some_and(|value| value.current == 0))
+                    .map_or(WORLD_TOUR_WORLDS.len() - 1, world_of_event)
+            });
+        watchers.current_world.update_infallible(current_world);
+    }
+    let current_world = watchers.current_world.pair.map(|value| value.current);
+
+    // Keep track of the furthest world reached during the run, so that moving on to a later
+    // world can mark a new segment, while going back to an earlier world doesn't
+    watchers.world_segment_entered = false;
+    if timer_state == TimerState::NotRunning {
+        watchers.furthest_world = None;
+    } else if let Some(current_world) = current_world {
+        match watchers.furthest_world {
+            Some(world) if current_world <= world => {}
+            Some(_) => {
+                watchers.furthest_world = Some(current_world);
+                watchers.world_segment_entered = true;
             }
-        },
+            None => watchers.furthest_world = Some(current_world),
+        }
+    }
+
+    watchers.all_stars_collected.update_infallible(
+        watchers
+            .world_tour_events()
+            .iter()
+            .enumerate()
+            .all(|(event, stars)| {
+                stars
+                    .pair
+                    .is_some_and(|value| value.current == WORLD_TOUR_EVENT_MAX_STARS[event])
+            }),
+    );
+    watchers.total_stars.update_infallible(
+        watchers
+            .world_tour_events()
+            .iter()
+            .map(|event| event.pair.map_or(0, |stars| stars.current as u32))
+            .sum(),
     );
 
-    let sunshine_coast = game
-        .read::<Address32>(addresses.sunshine_coast)
-        .unwrap_or_default();
-    let mut stars = game
-        .read::<[u8; 0x719]>(sunshine_coast)
-        .unwrap_or([0; 0x719]);
-    watchers.coastal_cruise.update_infallible(stars[0x7C]);
-    watchers.studio_scrapes.update_infallible(stars[0x138]);
-    watchers.battlezone_blast.update_infallible(stars[0x1F4]);
-    watchers.downtown_drift.update_infallible(stars[0x2B0]);
-    watchers.monkey_mayhem.update_infallible(stars[0x36C]);
-    watchers.starry_speedway.update_infallible(stars[0x428]);
-    watchers.roulette_rush.update_infallible(stars[0x4E4]);
-    watchers.canyon_carnage.update_infallible(stars[0x5A0]);
-
-    let frozen_valley = game
-        .read::<Address32>(addresses.sunshine_coast + 0x4)
-        .unwrap_or_default();
-    stars = game
-        .read::<[u8; 0x719]>(frozen_valley)
-        .unwrap_or([0; 0x719]);
-    watchers.snowball_shakedown.update_infallible(stars[0x7C]);
-    watchers.banana_boost.update_infallible(stars[0x138]);
-    watchers.shinobi_scramble.update_infallible(stars[0x1F4]);
-    watchers.seaside_scrap.update_infallible(stars[0x2B0]);
-    watchers.tricky_traffic.update_infallible(stars[0x36C]);
-    watchers.studio_scurry.update_infallible(stars[0x428]);
-    watchers.graffiti_groove.update_infallible(stars[0x4E4]);
-    watchers.shaking_skies.update_infallible(stars[0x5A0]);
-    watchers.neon_knockout.update_infallible(stars[0x65C]);
-    watchers.pirate_plunder.update_infallible(stars[0x718]);
-
-    let scorching_skies = game
-        .read::<Address32>(addresses.sunshine_coast + 0x8)
-        .unwrap_or_default();
-    stars = game
-        .read::<[u8; 0x719]>(scorching_skies)
-        .unwrap_or([0; 0x719]);
-    watchers.adder_assault.update_infallible(stars[0x7C]);
-    watchers.dreamy_drive.update_infallible(stars[0x138]);
-    watchers.sanctuary_speedway.update_infallible(stars[0x1F4]);
-    watchers.keils_carnage.update_infallible(stars[0x2B0]);
-    watchers.carrier_crisis.update_infallible(stars[0x36C]);
-    watchers.sunshine_slide.update_infallible(stars[0x428]);
-    watchers.rogue_rings.update_infallible(stars[0x4E4]);
-    watchers.seaside_skirmish.update_infallible(stars[0x5A0]);
-    watchers.shrine_time.update_infallible(stars[0x65C]);
-    watchers.hangar_hassle.update_infallible(stars[0x718]);
-
-    let twilight_engine = game
-        .read::<Address32>(addresses.sunshine_coast + 0xC)
-        .unwrap_or_default();
-    stars = game
-        .read::<[u8; 0x719]>(twilight_engine)
-        .unwrap_or([0; 0x719]);
-    watchers.booty_boost.update_infallible(stars[0x7C]);
-    watchers.racing_rangers.update_infallible(stars[0x138]);
-    watchers.shinobi_showdown.update_infallible(stars[0x1F4]);
-    watchers.ruin_run.update_infallible(stars[0x2B0]);
-    watchers.monkey_brawl.update_infallible(stars[0x36C]);
-    watchers.crumbling_chaos.update_infallible(stars[0x428]);
-    watchers.hatcher_hustle.update_infallible(stars[0x4E4]);
-    watchers.death_egg_duel.update_infallible(stars[0x5A0]);
+    // Keep track of how long ago the timer was started, for the grace period of the resets
+    if timer_state == TimerState::NotRunning {
+        watchers.ticks_since_timer_start = 0;
+    } else {
+        watchers.ticks_since_timer_start = watchers.ticks_since_timer_start.saturating_add(1);
+    }
+
+    // Keep track of how long ago a race was started, as the previous race's values
+    // may still be reported on the first few ticks
+    if watchers
+        .run_start
+        .pair
+        .is_some_and(|value| value.changed_to(&true))
+        || watchers
+            .race_status
+            .pair
+            .is_some_and(|value| value.changed_to(&RACE_STATUS_RACING))
+    {
+        watchers.ticks_since_start = 0;
+    } else {
+        watchers.ticks_since_start = watchers.ticks_since_start.saturating_add(1);
+    }
+
+    // The race timer being reset only counts as abandoning the race if the race was running
+    // on the previous tick. The timer may toggle to zero during the countdown, while the race
+    // isn't running yet, which must not be mistaken for a restart. The race status can't be
+    // required to still be racing on the current tick, as restarting leaves the racing state.
+    let race_abandoned = watchers
+        .igt
+        .pair
+        .is_some_and(|value| value.changed_to(&Duration::ZERO))
+        && watchers
+            .race_status
+            .pair
+            .is_some_and(|value| value.old == RACE_STATUS_RACING);
+
+    // There's no known memory location for the pause menu selection, so the way the player
+    // left an unfinished race is inferred: quitting unloads the race (`run_start` goes false),
+    // while retrying resets the race timer without unloading it. Unloading only counts as
+    // quitting if the race has been running since it was loaded, as the race status leaves
+    // the racing state while the pause menu is open. A race unloaded during its countdown
+    // isn't a quit.
+    watchers.race_in_progress |= racing;
+    watchers.race_exit.update_infallible({
+        let race_completed = watchers
+            .race_completed
+            .pair
+            .is_some_and(|value| value.current);
+
+        match watchers.run_start.pair {
+            Some(run_start)
+                if run_start.changed_to(&false) && watchers.race_in_progress && !race_completed =>
+            {
+                RaceExit::Quit
+            }
+            Some(run_start) if run_start.current && race_abandoned && !race_completed => {
+                RaceExit::Retry
+            }
+            _ if watchers
+                .race_status
+                .pair
+                .is_some_and(|value| value.changed_to(&RACE_STATUS_RACING)) =>
+            {
+                RaceExit::None
+            }
+            _ => watchers.race_exit.pair.unwrap_or_default().current,
+        }
+    });
+    if watchers.run_start.pair.is_some_and(|value| !value.current) {
+        watchers.race_in_progress = false;
+    }
+    let race_retried = watchers
+        .race_exit
+        .pair
+        .is_some_and(|value| value.changed_to(&RaceExit::Retry));
+
+    // Quitting a race is only confirmed once the menu state has held for a few ticks, with
+    // the race staying unloaded and not racing, so transient reads during loads can't reset
+    // the run. The race timer can't be used for this, as its last value is kept once the
+    // race is unloaded.
+    let in_menu = watchers.run_start.pair.is_some_and(|value| !value.current)
+        && watchers
+            .race_status
+            .pair
+            .is_some_and(|value| value.current != RACE_STATUS_RACING);
+    watchers.quit_confirmed = false;
+    watchers.quit_pending_ticks = match watchers.quit_pending_ticks {
+        _ if watchers
+            .race_exit
+            .pair
+            .is_some_and(|value| value.changed_to(&RaceExit::Quit)) =>
+        {
+            Some(0)
+        }
+        Some(ticks) if in_menu => {
+            let ticks = ticks.saturating_add(1);
+            watchers.quit_confirmed = ticks >= RESET_CONFIRMATION_TICKS;
+            (!watchers.quit_confirmed).then_some(ticks)
+        }
+        _ => None,
+    };
+
+    // The World Tour route starts over with each run. It only advances when splitting.
+    if timer_state == TimerState::NotRunning {
+        watchers.route_position = 0;
+    }
+
+    // Keep track of the tracks completed during the run, as a bitmask of `Tracks::mask`
+    let mut completed_tracks = watchers.completed_tracks.pair.unwrap_or_default().current;
+    if timer_state == TimerState::NotRunning {
+        completed_tracks = 0;
+    } else if watchers
+        .game_mode
+        .pair
+        .is_some_and(|gm| gm.current == GameMode::GandPrix || gm.current == GameMode::SingleRace)
+        && watchers
+            .race_completed
+            .pair
+            .is_some_and(|value| value.changed_to(&true))
+    {
+        if let Some(track) = &watchers.track_id.pair {
+            completed_tracks |= track.current.mask();
+        }
+    }
     watchers
-        .undertaker_overtaker
-        .update_infallible(stars[0x65C]);
-    watchers.golden_gauntlet.update_infallible(stars[0x718]);
-
-    let moonlight_park = game
-        .read::<Address32>(addresses.sunshine_coast + 0x10)
-        .unwrap_or_default();
-    stars = game
-        .read::<[u8; 0x719]>(moonlight_park)
-        .unwrap_or([0; 0x719]);
-    watchers.carnival_clash.update_infallible(stars[0x7C]);
-    watchers.curien_curves.update_infallible(stars[0x138]);
-    watchers.molten_mayhem.update_infallible(stars[0x1F4]);
-    watchers.speeding_seasons.update_infallible(stars[0x2B0]);
-    watchers.burning_boost.update_infallible(stars[0x36C]);
-    watchers.ocean_outrun.update_infallible(stars[0x428]);
-    watchers.billy_backslide.update_infallible(stars[0x4E4]);
-    watchers.carrier_charge.update_infallible(stars[0x5A0]);
-    watchers.jet_set_jaunt.update_infallible(stars[0x65C]);
-    watchers.arcade_annihilation.update_infallible(stars[0x718]);
-
-    let superstar_showdown = game
-        .read::<Address32>(addresses.sunshine_coast + 0x14)
-        .unwrap_or_default();
-    stars = game
-        .read::<[u8; 0x719]>(superstar_showdown)
-        .unwrap_or([0; 0x719]);
-    watchers.rapid_ruins.update_infallible(stars[0x7C]);
-    watchers.zombie_zoom.update_infallible(stars[0x138]);
-    watchers.maracar_madness.update_infallible(stars[0x1F4]);
-    watchers.nightmare_meander.update_infallible(stars[0x2B0]);
-    watchers.maraca_melee.update_infallible(stars[0x36C]);
-    watchers.castle_chaos.update_infallible(stars[0x428]);
-    watchers.volcano_velocity.update_infallible(stars[0x4E4]);
-    watchers.ranger_rush.update_infallible(stars[0x5A0]);
-    watchers.tokyo_takeover.update_infallible(stars[0x65C]);
-    watchers.fatal_finale.update_infallible(stars[0x718]);
-
-    if timer::state() == TimerState::NotRunning {
+        .completed_tracks
+        .update_infallible(completed_tracks);
+
+    // The time of a race is only accumulated once, even if the completion flag toggles
+    // again (e.g. when the results reload), until a new race genuinely starts
+    if watchers
+        .race_status
+        .pair
+        .is_some_and(|value| value.changed_to(&RACE_STATUS_RACING))
+    {
+        watchers.accumulated_for_race = false;
+    }
+
+    if timer_state == TimerState::NotRunning {
         watchers.total_igt = Duration::ZERO;
         watchers.progress_igt = Duration::ZERO;
+        watchers.run_ended = false;
+        watchers.credits_igt = None;
+        watchers.accumulated_for_race = false;
+        watchers.igt_capped = false;
+        watchers.auto_splits = 0;
+        watchers.pending_splits = 0;
+    } else if watchers.run_ended {
+        // The final time is kept once the run has been completed, whatever is played after it
     } else if let Some(race_completed) = &watchers.race_completed.pair {
+        // In Grand Prix, each split can optionally reflect the clean time of the completed
+        // races only, so the time of unfinished attempts is not counted
+        let clean_race_time = settings.gp_clean_race_time
+            && watchers
+                .game_mode
+                .pair
+                .is_some_and(|gm| gm.current == GameMode::GandPrix);
+
+        // When attaching with the timer already running, the time of the race in progress is
+        // picked up from the in-game timer right away. A race already on its results screen
+        // can optionally be accumulated too, as its completion would otherwise go unnoticed.
+        // Races completed before attaching can't be recovered, so game time still restarts
+        // from the race on screen.
+        let attached_on_results = settings.sync_on_midrun_attach
+            && watchers.ticks_since_attach == 1
+            && race_completed.current;
+
+        // The race timer keeps its last value once a race is quit, so the time of the quit
+        // race is explicitly discarded until the next race starts, rather than being counted
+        // when the timer goes back to zero
+        let race_quit = watchers
+            .race_exit
+            .pair
+            .is_some_and(|value| value.current == RaceExit::Quit);
+
         if let Some(igt) = &watchers.igt.pair {
             if !race_completed.current {
-                if let Some(race_status) = &watchers.race_status.pair {
-                    if igt.changed_to(&Duration::ZERO) && race_status.old == 4 {
-                        watchers.total_igt += igt.old;
+                if watchers.race_status.pair.is_some() {
+                    // Once a race has been accumulated, its time is already part of the
+                    // total, even if the completion flag drops while the results reload
+                    if clean_race_time || race_quit || watchers.accumulated_for_race {
+                        watchers.progress_igt = watchers.total_igt;
+                    } else if race_abandoned {
+                        if !(settings.discard_retried_time && race_retried) {
+                            watchers.total_igt += igt.old;
+                        }
                         watchers.progress_igt = watchers.total_igt;
                     } else {
                         watchers.progress_igt = watchers.total_igt + igt.current;
                     }
                 }
-            } else if race_completed.changed_to(&true) {
-                watchers.total_igt += {
-                    if (watchers
-                        .game_mode
-                        .pair
-                        .is_some_and(|gm| gm.current == GameMode::WorldTour)
-                        && required_laps == 0xFF)
-                        || watchers
-                            .event_type
-                            .pair
-                            .is_some_and(|et| et.current == 0xE64B5DD8)
-                    {
-                        igt.current
-                    } else {
-                        total_race_time
-                    }
+            } else if (race_completed.changed_to(&true) || attached_on_results)
+                && !watchers.accumulated_for_race
+            {
+                watchers.accumulated_for_race = true;
+                watchers.total_igt += if race_timed_by_igt(
+                    watchers.game_mode.pair.map(|gm| gm.current),
+                    required_laps,
+                    watchers.event_type.pair.map(|et| et.current),
+                ) {
+                    igt.current
+                } else {
+                    total_race_time
                 };
                 watchers.progress_igt = watchers.total_igt;
             }
         }
     }
+
+    // The in-game timer stops at the finish line, so the time spent on the results screen is
+    // optionally added on top of it, one tick at a time
+    if settings.count_results_time
+        && timer_state != TimerState::NotRunning
+        && !watchers.run_ended
+        && watchers
+            .results_screen
+            .pair
+            .is_some_and(|value| value.current)
+    {
+        let tick = Duration::seconds_f64(1.0 / DEFAULT_TICK_RATE);
+        watchers.total_igt += tick;
+        watchers.progress_igt += tick;
+    }
+
+    // The credits flag flips at a fixed point after the last race, so the time of the run
+    // ending on it is captured with an offset, to match where the run is meant to end. The
+    // accumulated time itself is left untouched.
+    if timer_state != TimerState::NotRunning
+        && settings.wt_end_source == WorldTourEndSource::CreditsFlag
+        && !watchers.run_ended
+        && watchers
+            .game_mode
+            .pair
+            .is_some_and(|gm| gm.current == GameMode::WorldTour)
+        && watchers
+            .end_credits
+            .pair
+            .is_some_and(|value| value.changed_to(&true))
+    {
+        let offset = Duration::milliseconds(settings.credits_split_offset_ms.milliseconds());
+        watchers.credits_igt = Some((watchers.progress_igt + offset).max(Duration::ZERO));
+    }
+
+    // An unforeseen edge case may make the accumulated time balloon, in which case it
+    // stops accumulating instead of running away
+    if watchers.progress_igt > MAX_TOTAL_IGT {
+        if !watchers.igt_capped {
+            asr::print_message("Accumulated game time exceeded the maximum, capping it");
+            watchers.igt_capped = true;
+        }
+        watchers.total_igt = watchers.total_igt.min(MAX_TOTAL_IGT);
+        watchers.progress_igt = MAX_TOTAL_IGT;
+    }
+}
+
+/// Returns the event type read from the game. It momentarily reads as 0 while the game is
+/// switching states, which would misclassify the race on completion, so the last valid value
+/// is kept instead, as it is when the read fails.
+fn event_type_or_last(event_type: Option<u32>, last: Option<Pair<u32>>) -> u32 {
+    event_type
+        .filter(|&event_type| event_type != 0)
+        .unwrap_or_else(|| last.unwrap_or_default().current)
+}
+
+/// Returns whether a completed race needs to be timed with the in-game timer rather than
+/// the total race time of the player, which isn't filled in for every kind of event
+fn race_timed_by_igt(
+    game_mode: Option<GameMode>,
+    required_laps: u8,
+    event_type: Option<u32>,
+) -> bool {
+    (game_mode == Some(GameMode::WorldTour) && required_laps == REQUIRED_LAPS_NONE)
+        || event_type == Some(EVENT_TYPE_IGT_TIMED)
+}
+
+/// Converts a race time read from the game, in seconds, to a `Duration` truncated to centiseconds.
+/// Returns `None` if the value is not a valid time, i.e. negative, not finite or longer than
+/// the maximum game time of a run, as it would otherwise be accumulated or overflow the
+/// conversion.
+fn race_time_from_secs(secs: f32) -> Option<Duration> {
+    if !secs.is_finite() || secs < 0.0 || secs > MAX_TOTAL_IGT.as_seconds_f32() {
+        return None;
+    }
+
+    Some(Duration::milliseconds((secs * 100.0) as i64 * 10))
+}
+
+/// Publishes the timer variables shown in the layout. When no race is loaded, the
+/// race-related variables are set to neutral placeholders instead of keeping stale values.
+fn update_variables(watchers: &Watchers, settings: &Settings) {
+    timer::set_variable("Next Split", next_split(watchers, settings).unwrap_or("-"));
+
+    if settings.debug_addresses {
+        let mut run_start_reads = VariableBuffer::<32>::new();
+        let [run_start, run_start_2] =
+            watchers
+                .run_start_reads
+                .map(|ok| if ok { "OK" } else { "Failed" });
+        let _ = write!(run_start_reads, "{run_start} / {run_start_2}");
+        timer::set_variable("Run Start Reads", run_start_reads.as_str());
+    }
+
+    let mut auto_splits = VariableBuffer::<10>::new();
+    let _ = write!(auto_splits, "{}", watchers.auto_splits);
+    timer::set_variable("Auto Splits", auto_splits.as_str());
+
+    timer::set_variable(
+        "Race Exit",
+        watchers.race_exit.pair.unwrap_or_default().current.name(),
+    );
+
+    // Only published once the input device has been read, which needs its address
+    if let Some(input_device) = watchers.input_device.pair {
+        timer::set_variable("Input", input_device.current.name());
+    }
+
+    // `required_laps` holds the lap count the race is set to, with `REQUIRED_LAPS_NONE`
+    // marking events without laps. It is only read from the race, so the variant stays
+    // unknown until a Time Attack race has been loaded.
+    timer::set_variable(
+        "TA Mode",
+        match (watchers.game_mode.pair, watchers.required_laps.pair) {
+            (Some(game_mode), Some(required_laps)) if game_mode.current == GameMode::TimeAttack => {
+                match required_laps.current {
+                    1 => "Single Lap",
+                    3 => "3 Lap",
+                    _ => "-",
+                }
+            }
+            _ => "-",
+        },
+    );
+
+    timer::set_variable(
+        "Playthrough",
+        match watchers.game_mode.pair {
+            Some(game_mode) if game_mode.current == GameMode::WorldTour => {
+                if world_tour_replay(watchers) {
+                    "Replay"
+                } else {
+                    "First"
+                }
+            }
+            _ => "-",
+        },
+    );
+
+    let mut tracks_done = VariableBuffer::<16>::new();
+    if settings.gp_marathon {
+        let _ = write!(
+            tracks_done,
+            "{}/{}",
+            watchers
+                .completed_tracks
+                .pair
+                .unwrap_or_default()
+                .current
+                .count_ones(),
+            TRACK_HASHES.len()
+        );
+    } else {
+        let _ = write!(tracks_done, "-");
+    }
+    timer::set_variable("Tracks Done", tracks_done.as_str());
+
+    let race_loaded = watchers
+        .race_status
+        .pair
+        .is_some_and(|value| value.current == RACE_STATUS_RACING)
+        || watchers
+            .race_completed
+            .pair
+            .is_some_and(|value| value.current);
+
+    match (race_loaded, watchers.igt.pair, watchers.track_id.pair) {
+        (true, Some(_), Some(track_id)) => {
+            timer::set_variable(
+                "Race Time",
+                format_duration(watchers.race_time_shown, DurationFormat::RaceClock).as_str(),
+            );
+            timer::set_variable("Track", track_id.current.name());
+
+            let (name, cup_name) = track_id.current.name_and_cup();
+            let (cup, position) = track_id.current.cup_and_position();
+            let mut track_cup = VariableBuffer::<48>::new();
+            let _ = write!(
+                track_cup,
+                "{name} ({cup_name} {}/{})",
+                position + 1,
+                CUP_SIZES[cup]
+            );
+            timer::set_variable("Track / Cup", track_cup.as_str());
+        }
+        _ => {
+            timer::set_variable("Race Time", "-");
+            timer::set_variable("Track", "-");
+            timer::set_variable("Track / Cup", "-");
+        }
+    }
+
+    if settings.export_state {
+        export_state(watchers);
+    }
+}
+
+/// Publishes the state of the splitter under a stable set of variable keys, so external
+/// tools can consume it
+fn export_state(watchers: &Watchers) {
+    timer::set_variable(
+        STATE_KEY_MODE,
+        watchers.game_mode.pair.map_or("-", |gm| gm.current.name()),
+    );
+    timer::set_variable(
+        STATE_KEY_TRACK,
+        watchers
+            .track_id
+            .pair
+            .map_or("-", |track| track.current.name()),
+    );
+
+    let mut race_status = VariableBuffer::<4>::new();
+    let _ = write!(
+        race_status,
+        "{}",
+        watchers.race_status.pair.unwrap_or_default().current
+    );
+    timer::set_variable(STATE_KEY_RACE_STATUS, race_status.as_str());
+
+    timer::set_variable(
+        STATE_KEY_IGT,
+        format_duration(
+            watchers.igt.pair.unwrap_or_default().current,
+            DurationFormat::RaceClock,
+        )
+        .as_str(),
+    );
+    timer::set_variable(
+        STATE_KEY_TOTAL_IGT,
+        format_duration(watchers.progress_igt, DurationFormat::RunClock).as_str(),
+    );
+
+    let mut total_stars = VariableBuffer::<4>::new();
+    let _ = write!(
+        total_stars,
+        "{}",
+        watchers.total_stars.pair.unwrap_or_default().current
+    );
+    timer::set_variable(STATE_KEY_STARS, total_stars.as_str());
+}
+
+/// Formats exported by `format_duration`
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DurationFormat {
+    /// `m:ss.cc`, the same way the game displays race times. The HUD uses this format
+    /// regardless of the language, and no display format setting is known, so it is fixed.
+    RaceClock,
+    /// `m:ss.cc`, or `h:mm:ss.cc` once over an hour, for times spanning a whole run
+    RunClock,
+}
+
+/// Formats a duration for a timer variable. Negative durations are prefixed with a minus sign.
+fn format_duration(time: Duration, format: DurationFormat) -> VariableBuffer<20> {
+    let centiseconds = time.whole_milliseconds() / 10;
+    let sign = if centiseconds < 0 { "-" } else { "" };
+    let centiseconds = centiseconds.unsigned_abs();
+    let (hours, minutes) = match format {
+        DurationFormat::RunClock if centiseconds >= 360_000 => {
+            (Some(centiseconds / 360_000), centiseconds / 6000 % 60)
+        }
+        _ => (None, centiseconds / 6000),
+    };
+
+    let mut buf = VariableBuffer::new();
+    let _ = match hours {
+        Some(hours) => write!(buf, "{sign}{hours}:{minutes:02}"),
+        None => write!(buf, "{sign}{minutes}"),
+    };
+    let _ = write!(
+        buf,
+        ":{:02}.{:02}",
+        centiseconds / 100 % 60,
+        centiseconds % 100
+    );
+    buf
+}
+
+/// A fixed capacity string buffer, used to format timer variables without allocating
+struct VariableBuffer<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> VariableBuffer<N> {
+    const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or_default()
+    }
+}
+
+impl<const N: usize> Write for VariableBuffer<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.buf
+            .get_mut(self.len..end)
+            .ok_or(fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
 }
 
 fn start(watchers: &Watchers, settings: &Settings) -> bool {
@@ -766,26 +2427,174 @@ fn start(watchers: &Watchers, settings: &Settings) -> bool {
         return false;
     }
 
-    if !watchers
-        .run_start
+    // The run can either start on character confirmation, or once the race actually begins
+    // for runners timing from the green light. When attaching while the game is already at
+    // the start gate, the confirmation can't be observed, so the first value read after
+    // attaching can optionally be used instead, on that tick only.
+    let started = if settings.start_on_race_active {
+        watchers
+            .race_status
+            .pair
+            .is_some_and(|value| value.changed_to(&RACE_STATUS_RACING))
+    } else {
+        watchers.run_start.pair.is_some_and(|value| {
+            value.changed_to(&true)
+                || (settings.start_if_already_at_gate
+                    && watchers.ticks_since_attach == 1
+                    && value.current)
+        })
+    };
+
+    if !started {
+        return false;
+    }
+
+    match watchers.game_mode.pair {
+        Some(x) => {
+            match x.current {
+                GameMode::GandPrix | GameMode::SingleRace => true,
+                GameMode::WorldTour => {
+                    let events = watchers.world_tour_events();
+
+                    settings.wt_start_ignore_stars
+                        || settings.wt_start_events.events().iter().all(|&event| {
+                            events[event].pair.is_some_and(|value| value.current == 0)
+                        })
+                        || (settings.wt_start_any_world
+                            && (!settings.wt_start_only_unstarted_world
+                                || next_world_unstarted(watchers)))
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Checks whether the save has already been through World Tour once, freeing the event
+/// selection. The first playthrough ends with Race of AGES in Moonlight Park, so a save whose
+/// star total holds more than the events before it, or where the player has reached
+/// Superstar Showdown, counts as a replay. This doesn't change the start conditions: by
+/// default a run only starts on a save without stars on the start events, which is a first
+/// playthrough. Replays need starting on any save file or in any world to be enabled.
+fn world_tour_replay(watchers: &Watchers) -> bool {
+    let last_world = WORLD_TOUR_WORLDS.len() - 1;
+    let first_playthrough_stars: u32 = watchers.world_tour_events()[..ARCADE_ANNIHILATION]
+        .iter()
+        .map(|event| event.pair.map_or(0, |stars| stars.current as u32))
+        .sum();
+
+    watchers
+        .total_stars
         .pair
-        .is_some_and(|value| value.changed_to(&true))
-    {
+        .is_some_and(|value| value.current > first_playthrough_stars)
+        || watchers.furthest_world == Some(last_world)
+        || watchers
+            .current_world
+            .pair
+            .is_some_and(|value| value.current == last_world)
+}
+
+/// Checks whether the world holding the first uncompleted World Tour event is entirely
+/// uncompleted, indicating a fresh attempt at that world
+fn next_world_unstarted(watchers: &Watchers) -> bool {
+    let events = watchers.world_tour_events();
+    events
+        .iter()
+        .position(|event| event.pair.is_some_and(|value| value.current == 0))
+        .is_some_and(|event| {
+            world_events(world_of_event(event))
+                .all(|event| events[event].pair.is_some_and(|value| value.current == 0))
+        })
+}
+
+/// Decides whether to split on this tick, keeping track of the state of the run. The extra
+/// events completed along with a split are queued, and split on the following ticks, one per
+/// tick, as long as splitting is allowed.
+fn auto_split(watchers: &mut Watchers, settings: &Settings) -> bool {
+    if !queues_splits(watchers, settings) {
+        watchers.pending_splits = 0;
+    }
+
+    if split(watchers, settings) {
+        watchers.run_ended = run_completed(watchers, settings);
+        watchers.pending_splits += pending_splits(watchers, settings);
+
+        // The route only moves on to its next event once that event has been split on
+        if let Some((position, event)) = next_route_event(watchers, settings) {
+            if event_completed(watchers, settings, event) {
+                watchers.route_position = position + 1;
+            }
+        }
+    } else if watchers.pending_splits > 0 && split_allowed(watchers, settings) {
+        watchers.pending_splits -= 1;
+    } else {
+        return false;
+    }
+
+    watchers.auto_splits += 1;
+    true
+}
+
+/// Checks whether any split can be triggered on this tick
+fn split_allowed(watchers: &Watchers, settings: &Settings) -> bool {
+    !watchers.run_ended
+        && watchers.ticks_since_start >= SPLIT_BUFFER_TICKS
+        && watchers.game_mode_valid
+        // Nothing to check if every split of the current mode is disabled, e.g. when the
+        // splitter is only used for auto starting and load removal
+        && watchers
+            .game_mode
+            .pair
+            .is_some_and(|gm| settings.splits_enabled(gm.current))
+}
+
+fn split(watchers: &Watchers, settings: &Settings) -> bool {
+    if !split_allowed(watchers, settings) {
         return false;
     }
 
+    if run_completed(watchers, settings) {
+        return true;
+    }
+
     match watchers.game_mode.pair {
         Some(x) => match x.current {
-            GameMode::GandPrix | GameMode::SingleRace => true,
             GameMode::WorldTour => {
+                (match settings.wt_world_split {
+                    WorldTourWorldSplit::Off => false,
+                    WorldTourWorldSplit::WorldEntry => world_entered(watchers),
+                    WorldTourWorldSplit::WorldSegment => watchers.world_segment_entered,
+                }) || if settings.wt_route == WorldTourRoute::Off {
+                    completed_events(watchers, settings) > 0
+                } else {
+                    next_route_event(watchers, settings)
+                        .is_some_and(|(_, event)| event_completed(watchers, settings, event))
+                }
+            }
+            GameMode::GandPrix | GameMode::SingleRace if settings.gp_marathon => {
                 watchers
-                    .coastal_cruise
+                    .completed_tracks
                     .pair
-                    .is_some_and(|value| value.current == 0)
+                    .is_some_and(|value| value.changed())
+                    && watchers
+                        .track_id
+                        .pair
+                        .is_some_and(|track| settings.track_enabled(track.current))
+            }
+            GameMode::GandPrix if settings.gp_split_on_cup_results => cup_results(watchers),
+            GameMode::GandPrix | GameMode::SingleRace => {
+                let race_finished = if settings.split_on_results_screen {
+                    watchers.results_screen.pair
+                } else {
+                    watchers.race_completed.pair
+                };
+
+                race_finished.is_some_and(|value| value.changed_to(&true))
                     && watchers
-                        .canyon_carnage
+                        .track_id
                         .pair
-                        .is_some_and(|value| value.current == 0)
+                        .is_some_and(|track| settings.track_enabled(track.current))
             }
             _ => false,
         },
@@ -793,371 +2602,329 @@ fn start(watchers: &Watchers, settings: &Settings) -> bool {
     }
 }
 
-fn split(watchers: &Watchers, settings: &Settings) -> bool {
-    match watchers.game_mode.pair {
-        Some(x) => match x.current {
-            GameMode::WorldTour => {
-                (watchers
-                    .coastal_cruise
-                    .pair
-                    .is_some_and(|value| value.increased())
-                    && settings.coastal_cruise)
-                    || (watchers
-                        .studio_scrapes
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.studio_scrapes)
-                    || (watchers
-                        .battlezone_blast
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.battlezone_blast)
-                    || (watchers
-                        .downtown_drift
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.downtown_drift)
-                    || (watchers
-                        .monkey_mayhem
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.monkey_mayhem)
-                    || (watchers
-                        .starry_speedway
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.starry_speedway)
-                    || (watchers
-                        .roulette_rush
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.roulette_rush)
-                    || (watchers
-                        .canyon_carnage
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.canyon_carnage)
-                    || (watchers
-                        .snowball_shakedown
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.snowball_shakedown)
-                    || (watchers
-                        .banana_boost
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.banana_boost)
-                    || (watchers
-                        .shinobi_scramble
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.shinobi_scramble)
-                    || (watchers
-                        .seaside_scrap
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.seaside_scrap)
-                    || (watchers
-                        .tricky_traffic
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.tricky_traffic)
-                    || (watchers
-                        .studio_scurry
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.studio_scurry)
-                    || (watchers
-                        .graffiti_groove
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.graffiti_groove)
-                    || (watchers
-                        .shaking_skies
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.shaking_skies)
-                    || (watchers
-                        .neon_knockout
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.neon_knockout)
-                    || (watchers
-                        .pirate_plunder
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.pirate_plunder)
-                    || (watchers
-                        .adder_assault
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.adder_assault)
-                    || (watchers
-                        .dreamy_drive
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.dreamy_drive)
-                    || (watchers
-                        .sanctuary_speedway
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.sanctuary_speedway)
-                    || (watchers
-                        .keils_carnage
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.keils_carnage)
-                    || (watchers
-                        .carrier_crisis
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.carrier_crisis)
-                    || (watchers
-                        .sunshine_slide
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.sunshine_slide)
-                    || (watchers
-                        .rogue_rings
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.rogue_rings)
-                    || (watchers
-                        .seaside_skirmish
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.seaside_skirmish)
-                    || (watchers
-                        .shrine_time
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.shrine_time)
-                    || (watchers
-                        .hangar_hassle
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.hangar_hassle)
-                    || (watchers
-                        .booty_boost
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.booty_boost)
-                    || (watchers
-                        .racing_rangers
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.racing_rangers)
-                    || (watchers
-                        .shinobi_showdown
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.shinobi_showdown)
-                    || (watchers
-                        .ruin_run
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.ruin_run)
-                    || (watchers
-                        .monkey_brawl
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.monkey_brawl)
-                    || (watchers
-                        .crumbling_chaos
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.crumbling_chaos)
-                    || (watchers
-                        .hatcher_hustle
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.hatcher_hustle)
-                    || (watchers
-                        .death_egg_duel
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.death_egg_duel)
-                    || (watchers
-                        .undertaker_overtaker
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.undertaker_overtaker)
-                    || (watchers
-                        .golden_gauntlet
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.golden_gauntlet)
-                    || (watchers
-                        .carnival_clash
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.carnival_clash)
-                    || (watchers
-                        .curien_curves
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.curien_curves)
-                    || (watchers
-                        .molten_mayhem
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.molten_mayhem)
-                    || (watchers
-                        .speeding_seasons
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.speeding_seasons)
-                    || (watchers
-                        .burning_boost
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.burning_boost)
-                    || (watchers
-                        .ocean_outrun
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.ocean_outrun)
-                    || (watchers
-                        .billy_backslide
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.billy_backslide)
-                    || (watchers
-                        .carrier_charge
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.carrier_charge)
-                    || (watchers
-                        .jet_set_jaunt
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.jet_set_jaunt)
-                    || (watchers
-                        .arcade_annihilation
-                        .pair
-                        .is_some_and(|value| value.changed_to(&4))
-                        && settings.arcade_annihilation)
-                    || (watchers
-                        .end_credits
-                        .pair
-                        .is_some_and(|value| value.changed_to(&true))
-                        && watchers
-                            .arcade_annihilation
-                            .pair
-                            .is_some_and(|value| value.current != 4)
-                        && settings.arcade_annihilation)
-                    || (watchers
-                        .rapid_ruins
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.rapid_ruins)
-                    || (watchers
-                        .zombie_zoom
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.zombie_zoom)
-                    || (watchers
-                        .maracar_madness
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.maracar_madness)
-                    || (watchers
-                        .nightmare_meander
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.nightmare_meander)
-                    || (watchers
-                        .maraca_melee
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.maraca_melee)
-                    || (watchers
-                        .castle_chaos
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.castle_chaos)
-                    || (watchers
-                        .volcano_velocity
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.volcano_velocity)
-                    || (watchers
-                        .ranger_rush
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.ranger_rush)
-                    || (watchers
-                        .tokyo_takeover
-                        .pair
-                        .is_some_and(|value| value.increased())
-                        && settings.tokyo_takeover)
-                    || (watchers
-                        .fatal_finale
-                        .pair
-                        .is_some_and(|value| value.increased() && value.current != 4)
-                        && settings.fatal_finale)
-                    || (watchers
-                        .end_credits
+/// Returns the next event expected on the World Tour route, along with its position in the
+/// route. Only the next event is considered, so events completed out of order are ignored.
+/// Events whose split is disabled are skipped.
+fn next_route_event(watchers: &Watchers, settings: &Settings) -> Option<(usize, usize)> {
+    let enabled = settings.world_tour_events();
+    settings
+        .wt_route
+        .events()
+        .iter()
+        .enumerate()
+        .skip(watchers.route_position)
+        .find(|&(_, &event)| enabled[event])
+        .map(|(position, &event)| (position, event))
+}
+
+/// Counts the enabled World Tour events that have just been completed. Several of them may
+/// gain stars on the same tick, e.g. after a skipped tick.
+fn completed_events(watchers: &Watchers, settings: &Settings) -> usize {
+    let enabled = settings.world_tour_events();
+    (0..WORLD_TOUR_EVENTS)
+        .filter(|&event| enabled[event] && event_completed(watchers, settings, event))
+        .count()
+}
+
+/// Checks whether the cup results of a Grand Prix have just appeared. They are shown
+/// as soon as the player leaves the results screen of the last race of the cup.
+fn cup_results(watchers: &Watchers) -> bool {
+    watchers
+        .results_screen
+        .pair
+        .is_some_and(|value| value.changed_to(&false))
+        && watchers
+            .track_id
+            .pair
+            .is_some_and(|track| track.current.last_in_cup())
+}
+
+/// Checks whether a World Tour event has just been completed, gaining stars for doing so
+fn event_completed(watchers: &Watchers, settings: &Settings, event: usize) -> bool {
+    let Some(stars) = watchers.world_tour_events()[event].pair else {
+        return false;
+    };
+    let end_credits = watchers
+        .end_credits
+        .pair
+        .is_some_and(|value| value.changed_to(&true));
+
+    let max_stars = WORLD_TOUR_EVENT_MAX_STARS[event];
+    let split_mode = match event {
+        ARCADE_ANNIHILATION => settings.arcade_split_mode,
+        FATAL_FINALE => settings.finale_split_mode,
+        _ => return stars.increased(),
+    };
+
+    match (split_mode, event) {
+        (FinalEventSplitMode::MaxValue, _) => stars.changed_to(&max_stars),
+        (FinalEventSplitMode::AnyIncrease, _) => stars.increased(),
+        // Race of AGES in Moonlight Park ends the Any% category, so its first completion only
+        // splits once the credits are reached. Gaining the last star after unlocking S-Class
+        // splits right away.
+        (FinalEventSplitMode::Credits, ARCADE_ANNIHILATION) => {
+            stars.changed_to(&max_stars) || (end_credits && stars.current != max_stars)
+        }
+        // Race of AGES in Superstar Showdown ends the 100% category, so the completion giving
+        // the last star only splits once the credits are reached
+        (FinalEventSplitMode::Credits, _) => {
+            (stars.increased() && stars.current != max_stars)
+                || (end_credits && stars.current == max_stars)
+        }
+    }
+}
+
+/// Checks whether every event of a world holds a valid star rank in its star block
+fn star_block_valid(stars: &[u8], world: usize) -> bool {
+    let first_event = world_events(world).start;
+    world_events(world)
+        .all(|event| stars[STAR_OFFSETS[event - first_event]] <= WORLD_TOUR_EVENT_MAX_STARS[event])
+}
+
+/// Returns the range of event indices belonging to a World Tour world
+fn world_events(world: usize) -> core::ops::Range<usize> {
+    let first_event = WORLD_TOUR_WORLDS[world].1;
+    let next_world = WORLD_TOUR_WORLDS.get(world + 1);
+    first_event..next_world.map_or(WORLD_TOUR_EVENTS, |&(_, first_event)| first_event)
+}
+
+/// Returns the index of the World Tour world an event belongs to
+fn world_of_event(event: usize) -> usize {
+    WORLD_TOUR_WORLDS
+        .iter()
+        .rposition(|&(_, first_event)| first_event <= event)
+        .unwrap_or_default()
+}
+
+/// Checks whether the first event of any World Tour world has just been cleared
+/// for the first time, marking the transition into that world.
+fn world_entered(watchers: &Watchers) -> bool {
+    let events = watchers.world_tour_events();
+    WORLD_TOUR_WORLDS.iter().any(|&(_, first_event)| {
+        events[first_event]
+            .pair
+            .is_some_and(|value| value.changed_from(&0))
+    })
+}
+
+/// Checks whether splits can be queued, which is only the case when splitting on every
+/// World Tour event, as a route only advances by one event at a time
+fn queues_splits(watchers: &Watchers, settings: &Settings) -> bool {
+    watchers
+        .game_mode
+        .pair
+        .is_some_and(|gm| gm.current == GameMode::WorldTour)
+        && settings.wt_route == WorldTourRoute::Off
+}
+
+/// Returns the number of splits to queue along with the split that was just triggered, one
+/// per extra World Tour event completed on this tick. Only the stars gained on this tick are
+/// counted, so the events queued on previous ticks aren't counted again.
+fn pending_splits(watchers: &Watchers, settings: &Settings) -> usize {
+    if watchers.run_ended || !queues_splits(watchers, settings) {
+        return 0;
+    }
+    completed_events(watchers, settings).saturating_sub(1)
+}
+
+/// Checks whether the final split of the run has been reached. Once this returns true,
+/// no further splits will be triggered until the timer is reset.
+fn run_completed(watchers: &Watchers, settings: &Settings) -> bool {
+    match watchers.game_mode.pair.map(|gm| gm.current) {
+        Some(GameMode::WorldTour) if settings.wt_end_source == WorldTourEndSource::CreditsFlag => {
+            watchers
+                .end_credits
+                .pair
+                .is_some_and(|value| value.changed_to(&true))
+        }
+        Some(GameMode::WorldTour) => {
+            (settings.wt_hundred_percent
+                && watchers
+                    .all_stars_collected
+                    .pair
+                    .is_some_and(|value| value.changed_to(&true)))
+                || settings.wt_target_stars.stars().is_some_and(|target| {
+                    watchers
+                        .total_stars
                         .pair
-                        .is_some_and(|value| value.changed_to(&true))
-                        && watchers
-                            .fatal_finale
-                            .pair
-                            .is_some_and(|value| value.current == 4)
-                        && settings.fatal_finale)
-            }
-            GameMode::GandPrix | GameMode::SingleRace => {
-                watchers
-                    .race_completed
+                        .is_some_and(|value| value.old < target && value.current >= target)
+                })
+        }
+        Some(GameMode::GandPrix) if settings.gp_end_detection => final_cup_results(watchers),
+        Some(GameMode::GandPrix | GameMode::SingleRace) => {
+            settings.gp_marathon
+                && watchers
+                    .completed_tracks
                     .pair
-                    .is_some_and(|value| value.changed_to(&true))
-                    && match watchers.track_id.pair {
-                        Some(x) => match x.current {
-                            Tracks::OceanView => settings.ocean_view,
-                            Tracks::SambaStudios => settings.samba_studios,
-                            Tracks::CarrierZone => settings.carrier_zone,
-                            Tracks::DragonCanyon => settings.dragon_canyon,
-                            Tracks::TempleTrouble => settings.temple_trouble,
-                            Tracks::GalacticParade => settings.galactic_parade,
-                            Tracks::SeasonalShrines => settings.seasonal_shrines,
-                            Tracks::RoguesLanding => settings.rogues_landing,
-                            Tracks::DreamValley => settings.dream_valley,
-                            Tracks::ChillyCastle => settings.chilly_castle,
-                            Tracks::GraffitiCity => settings.graffiti_city,
-                            Tracks::SanctuaryFalls => settings.sanctuary_falls,
-                            Tracks::GraveyardGig => settings.graveyard_gig,
-                            Tracks::AddersLair => settings.adders_lair,
-                            Tracks::BurningDepths => settings.burning_depths,
-                            Tracks::RaceOfAges => settings.race_of_ages,
-                            Tracks::SushineTour => settings.sunshine_tour,
-                            Tracks::ShibuyaDowntown => settings.shibuya_downtown,
-                            Tracks::RouletteRoad => settings.roulette_road,
-                            Tracks::EggHangar => settings.egg_hangar,
-                            Tracks::OutrunBay => settings.outrun_bay,
-                        },
-                        _ => false,
-                    }
-            }
-            _ => false,
-        },
+                    .is_some_and(|value| value.changed_to(&ALL_TRACKS_MASK))
+        }
         _ => false,
     }
 }
 
-fn reset(_watchers: &Watchers, _settings: &Settings) -> bool {
-    false
+/// Checks whether the cup results of the last Grand Prix cup have just appeared, ending
+/// an All-Cups run
+fn final_cup_results(watchers: &Watchers) -> bool {
+    cup_results(watchers)
+        && watchers
+            .track_id
+            .pair
+            .is_some_and(|track| track.current.cup() == CUP_NAMES.len() - 1)
+}
+
+/// Returns the name of the event the next split is expected on, given the current game mode
+/// and the splits that are enabled in the settings.
+fn next_split(watchers: &Watchers, settings: &Settings) -> Option<&'static str> {
+    match watchers.game_mode.pair?.current {
+        GameMode::WorldTour if settings.wt_route != WorldTourRoute::Off => {
+            next_route_event(watchers, settings).map(|(_, event)| WORLD_TOUR_EVENT_NAMES[event])
+        }
+        GameMode::WorldTour => {
+            let enabled = settings.world_tour_events();
+            watchers
+                .world_tour_events()
+                .iter()
+                .zip(enabled)
+                .zip(WORLD_TOUR_EVENT_NAMES)
+                .find(|((event, enabled), _)| {
+                    *enabled && event.pair.is_some_and(|value| value.current == 0)
+                })
+                .map(|(_, name)| name)
+        }
+        GameMode::GandPrix | GameMode::SingleRace => Some(watchers.track_id.pair?.current.name()),
+        _ => None,
+    }
+}
+
+fn reset(watchers: &Watchers, settings: &Settings, timer_state: TimerState) -> bool {
+    if post_credits_reset(watchers, settings, timer_state) {
+        return true;
+    }
+
+    // Transient reads during the start sequence shouldn't be able to reset the run. The grace
+    // period counts from the start of the timer rather than from the game time, which stays at
+    // zero until the first race is under way.
+    let elapsed_ms = watchers.ticks_since_timer_start as f64 * 1000.0 / DEFAULT_TICK_RATE;
+    if elapsed_ms < settings.reset_grace_ms.milliseconds() as f64 {
+        return false;
+    }
+
+    settings.reset && watchers.quit_confirmed
 }
 
-fn is_loading(_watchers: &Watchers, _settings: &Settings) -> Option<bool> {
+/// Checks whether the game is returning to the menus once the credits are over, after the
+/// run has been completed, so the timer can be reset for a fresh attempt
+fn post_credits_reset(watchers: &Watchers, settings: &Settings, timer_state: TimerState) -> bool {
+    settings.reset
+        && settings.reset_after_credits
+        && (watchers.run_ended || timer_state == TimerState::Ended)
+        && watchers
+            .end_credits
+            .pair
+            .is_some_and(|value| value.changed_to(&false))
+}
+
+fn is_loading(watchers: &Watchers, settings: &Settings) -> Option<bool> {
+    if settings.count_results_time
+        && watchers
+            .results_screen
+            .pair
+            .is_some_and(|value| value.current)
+    {
+        return Some(false);
+    }
+
+    // Without the in-game time overriding it, game time runs in real time while racing only.
+    // It can optionally be paused while the in-game timer stalls, so external pauses aren't
+    // counted. With the override, game time already stops along with the in-game timer.
+    if !settings.override_game_time {
+        return Some(
+            !watchers
+                .race_status
+                .pair
+                .is_some_and(|value| value.current == RACE_STATUS_RACING)
+                || (settings.pause_on_igt_stall && watchers.igt_stalled_ticks >= IGT_STALL_TICKS),
+        );
+    }
+
     Some(true)
 }
 
-fn game_time(
-    watchers: &Watchers,
-    _settings: &Settings,
-    _addresses: &Addresses,
-) -> Option<Duration> {
-    Some(watchers.progress_igt)
+fn game_time(watchers: &Watchers, settings: &Settings) -> Option<Duration> {
+    if !settings.override_game_time {
+        return None;
+    }
+
+    if watchers.run_ended {
+        return Some(watchers.credits_igt.unwrap_or(watchers.progress_igt));
+    }
+
+    // Each game mode can report its own source of game time, falling back to the in-game time
+    // accumulated over the run
+    let game_time = match watchers.game_mode.pair.map(|gm| gm.current) {
+        Some(GameMode::SingleRace) => single_race_game_time(watchers, settings),
+        _ => None,
+    };
+
+    Some(game_time.unwrap_or(watchers.progress_igt))
+}
+
+fn single_race_game_time(watchers: &Watchers, settings: &Settings) -> Option<Duration> {
+    // The final race time can be reported instead once the race is completed
+    if settings.single_race_use_final_time
+        && watchers
+            .race_completed
+            .pair
+            .is_some_and(|value| value.current)
+    {
+        Some(watchers.total_race_time.pair?.current)
+    } else {
+        None
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum RaceExit {
+    #[default]
+    None,
+    Retry,
+    Quit,
+}
+
+impl RaceExit {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::None => "-",
+            Self::Retry => "Retry",
+            Self::Quit => "Quit",
+        }
+    }
+}
+
+/// Input device the game is being played with, for stream overlays
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum InputDevice {
+    Keyboard,
+    Controller,
+}
+
+impl InputDevice {
+    /// Decodes the value read from the game. The encoding is expected to be a flag set while
+    /// a controller is in use, which has to be confirmed along with the signature.
+    const fn from_raw(raw: u8) -> Option<Self> {
+        match raw {
+            0 => Some(Self::Keyboard),
+            1 => Some(Self::Controller),
+            _ => None,
+        }
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Keyboard => "Keyboard",
+            Self::Controller => "Controller",
+        }
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -1168,6 +2935,17 @@ enum GameMode {
     SingleRace,
 }
 
+impl GameMode {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::WorldTour => "World Tour",
+            Self::GandPrix => "Grand Prix",
+            Self::TimeAttack => "Time Attack",
+            Self::SingleRace => "Single Race",
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum Tracks {
     OceanView,
@@ -1193,4 +2971,1985 @@ enum Tracks {
     OutrunBay,
 }
 
-const PROCESS_NAME: &str = "ASN_App_PcDx9_Final.exe";
+impl Tracks {
+    fn from_hash(hash: u32) -> Option<Self> {
+        TRACK_HASHES
+            .iter()
+            .find(|&&(track_hash, _)| track_hash == hash)
+            .map(|&(_, track)| track)
+    }
+
+    /// Returns the index of the Grand Prix cup the track belongs to, in `CUP_NAMES`, along
+    /// with the position of the track within that cup
+    const fn cup_and_position(self) -> (usize, usize) {
+        match self {
+            Self::OceanView => (0, 0),
+            Self::SambaStudios => (0, 1),
+            Self::CarrierZone => (0, 2),
+            Self::DragonCanyon => (0, 3),
+            Self::TempleTrouble => (1, 0),
+            Self::GalacticParade => (1, 1),
+            Self::SeasonalShrines => (1, 2),
+            Self::RoguesLanding => (1, 3),
+            Self::DreamValley => (2, 0),
+            Self::ChillyCastle => (2, 1),
+            Self::GraffitiCity => (2, 2),
+            Self::SanctuaryFalls => (2, 3),
+            Self::GraveyardGig => (3, 0),
+            Self::AddersLair => (3, 1),
+            Self::BurningDepths => (3, 2),
+            Self::RaceOfAges => (3, 3),
+            Self::SushineTour => (4, 0),
+            Self::ShibuyaDowntown => (4, 1),
+            Self::RouletteRoad => (4, 2),
+            Self::EggHangar => (4, 3),
+            Self::OutrunBay => (4, 4),
+        }
+    }
+
+    /// Returns the index of the Grand Prix cup the track belongs to, in `CUP_NAMES`
+    const fn cup(self) -> usize {
+        self.cup_and_position().0
+    }
+
+    /// Returns the bit representing the track in a bitmask of tracks
+    const fn mask(self) -> u32 {
+        1 << self as u32
+    }
+
+    /// Returns the display name of the track along with the name of its Grand Prix cup
+    const fn name_and_cup(self) -> (&'static str, &'static str) {
+        (self.name(), CUP_NAMES[self.cup()])
+    }
+
+    /// Returns whether the track is the last race of its Grand Prix cup
+    const fn last_in_cup(self) -> bool {
+        let (cup, position) = self.cup_and_position();
+        position + 1 == CUP_SIZES[cup]
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::OceanView => "Ocean View",
+            Self::SambaStudios => "Samba Studios",
+            Self::CarrierZone => "Carrier Zone",
+            Self::DragonCanyon => "Dragon Canyon",
+            Self::TempleTrouble => "Temple Trouble",
+            Self::GalacticParade => "Galactic Parade",
+            Self::SeasonalShrines => "Seasonal Shrines",
+            Self::RoguesLanding => "Rogue's Landing",
+            Self::DreamValley => "Dream Valley",
+            Self::ChillyCastle => "Chilly Castle",
+            Self::GraffitiCity => "Graffiti City",
+            Self::SanctuaryFalls => "Sanctuary Falls",
+            Self::GraveyardGig => "Graveyard Gig",
+            Self::AddersLair => "Adder's Lair",
+            Self::BurningDepths => "Burning Depths",
+            Self::RaceOfAges => "Race of AGES",
+            Self::SushineTour => "Sunshine Tour",
+            Self::ShibuyaDowntown => "Shibuya Downtown",
+            Self::RouletteRoad => "Roulette Road",
+            Self::EggHangar => "Egg Hangar",
+            Self::OutrunBay => "Outrun Bay",
+        }
+    }
+}
+
+/// Names of the Grand Prix cups
+const CUP_NAMES: [&str; 5] = [
+    "Dragon Cup",
+    "Emerald Cup",
+    "Arcade Cup",
+    "Classic Cup",
+    "Bonus Cup",
+];
+
+/// Number of tracks in each Grand Prix cup, in the order of `CUP_NAMES`
+const CUP_SIZES: [usize; CUP_NAMES.len()] = [4, 4, 4, 4, 5];
+
+// The cups must hold every track exactly, and each track must fit within its cup
+const _: () = {
+    let mut tracks = 0;
+    let mut cup = 0;
+    while cup < CUP_SIZES.len() {
+        tracks += CUP_SIZES[cup];
+        cup += 1;
+    }
+    assert!(tracks == TRACK_HASHES.len());
+
+    let mut i = 0;
+    while i < TRACK_HASHES.len() {
+        let (cup, position) = TRACK_HASHES[i].1.cup_and_position();
+        assert!(position < CUP_SIZES[cup]);
+        i += 1;
+    }
+};
+
+/// Bitmask of `Tracks::mask` with every track set
+const ALL_TRACKS_MASK: u32 = (1 << TRACK_HASHES.len()) - 1;
+
+/// Hashes the game uses to identify each track, as read through the `event_type` pointer
+const TRACK_HASHES: [(u32, Tracks); 21] = [
+    (0xD4257EBD, Tracks::OceanView),
+    (0x32D305A8, Tracks::SambaStudios),
+    (0xC72B3B98, Tracks::CarrierZone),
+    (0x03EB7FFF, Tracks::DragonCanyon),
+    (0xE3121777, Tracks::TempleTrouble),
+    (0x4E015AB6, Tracks::GalacticParade),
+    (0x503C1CBC, Tracks::SeasonalShrines),
+    (0x7534B7CA, Tracks::RoguesLanding),
+    (0x38A394ED, Tracks::DreamValley),
+    (0xC5C9DEA1, Tracks::ChillyCastle),
+    (0xD936550C, Tracks::GraffitiCity),
+    (0x4A0FF7AE, Tracks::SanctuaryFalls),
+    (0xCD8017BA, Tracks::GraveyardGig),
+    (0xDC93F18B, Tracks::AddersLair),
+    (0x2DB91FC2, Tracks::BurningDepths),
+    (0x94610644, Tracks::RaceOfAges),
+    (0xE6CD97F0, Tracks::SushineTour),
+    (0xE87FDF22, Tracks::ShibuyaDowntown),
+    (0x17463C8D, Tracks::RouletteRoad),
+    (0xFEBC639E, Tracks::EggHangar),
+    (0x1EF56CE1, Tracks::OutrunBay),
+];
+
+/// Known executable names of the game, starting with the one of the Steam release
+const PROCESS_NAMES: [&str; 3] = ["ASN_App_PcDx9_Final.exe", "ASRT.exe", "SASRT.exe"];
+/// Smallest plausible size of the main module's image. Anything below this is a bad read.
+const MIN_MODULE_SIZE: u64 = 0x1000;
+/// Plausible sizes of the main module's image for a 32-bit process, which can't map more
+/// than its 2 GB of user address space
+const X86_MODULE_SIZES: core::ops::RangeInclusive<u64> = MIN_MODULE_SIZE..=0x8000_0000;
+/// Size of the scan window used when the main module's size can't be determined
+const DEFAULT_MODULE_SIZE: u64 = 0x2000000;
+const RACE_STATUS_RACING: u8 = 4;
+/// Variable keys of the state published for companion tools
+const STATE_KEY_MODE: &str = "state:mode";
+const STATE_KEY_TRACK: &str = "state:track";
+const STATE_KEY_RACE_STATUS: &str = "state:race_status";
+const STATE_KEY_IGT: &str = "state:igt";
+const STATE_KEY_TOTAL_IGT: &str = "state:total_igt";
+const STATE_KEY_STARS: &str = "state:stars";
+/// Number of ticks the menu state needs to hold after quitting a race before the run resets
+const RESET_CONFIRMATION_TICKS: u8 = 10;
+/// Number of consecutive ticks with a failed game mode read after which the mode is stale
+const GAME_MODE_STALE_TICKS: u8 = 30;
+/// Number of attempts at resolving the addresses before trying another process
+const ADDRESSES_INIT_ATTEMPTS: u16 = 600;
+/// Number of ticks between two health checks of the resolved addresses
+const WATCHDOG_INTERVAL_TICKS: u16 = 120;
+/// Number of consecutive failed health checks after which the addresses are scanned again
+const WATCHDOG_FAILURE_THRESHOLD: u8 = 5;
+/// Number of consecutive ticks with failed reads after which the splitter enters standby
+const STANDBY_THRESHOLD_TICKS: u16 = 240;
+/// Tick rate of the splitter while in standby
+const STANDBY_TICK_RATE: f64 = 4.0;
+/// Default tick rate of the splitter
+const DEFAULT_TICK_RATE: f64 = 120.0;
+/// Required laps value of the World Tour events that aren't lap based, whose total race
+/// time is never filled in
+const REQUIRED_LAPS_NONE: u8 = 0xFF;
+/// Event type of the events whose total race time doesn't reflect the time spent racing,
+/// and are therefore timed with the in-game timer
+const EVENT_TYPE_IGT_TIMED: u32 = 0xE64B5DD8;
+/// Maximum game time accumulated over a run, well above the length of any category
+const MAX_TOTAL_IGT: Duration = Duration::hours(24);
+/// Number of ticks the in-game timer needs to stay still during a race to count as stalled
+const IGT_STALL_TICKS: u8 = 30;
+/// Number of ticks a new loading state needs to hold before game time is paused or resumed
+const LOADING_DEBOUNCE_TICKS: u8 = 3;
+/// Number of ticks after a race starts during which splitting is suppressed
+const SPLIT_BUFFER_TICKS: u8 = 1;
+
+/// Number of events in World Tour mode
+const WORLD_TOUR_EVENTS: usize = 58;
+/// Stars awarded for completing an event on the hardest difficulty (S-Class / Expert)
+const WORLD_TOUR_MAX_STARS: u8 = 4;
+/// Stars awarded for completing the boss event closing a world on the hardest difficulty
+const WORLD_TOUR_BOSS_MAX_STARS: u8 = 4;
+/// Highest star rank of each World Tour event, in the order they appear in game
+const WORLD_TOUR_EVENT_MAX_STARS: [u8; WORLD_TOUR_EVENTS] = world_tour_event_max_stars();
+
+/// Builds the highest star rank of each World Tour event, from the common one overridden by
+/// the one of the boss events, which close each world
+const fn world_tour_event_max_stars() -> [u8; WORLD_TOUR_EVENTS] {
+    let mut max_stars = [WORLD_TOUR_MAX_STARS; WORLD_TOUR_EVENTS];
+    let mut world = 1;
+    while world < WORLD_TOUR_WORLDS.len() {
+        max_stars[WORLD_TOUR_WORLDS[world].1 - 1] = WORLD_TOUR_BOSS_MAX_STARS;
+        world += 1;
+    }
+    max_stars[WORLD_TOUR_EVENTS - 1] = WORLD_TOUR_BOSS_MAX_STARS;
+    max_stars
+}
+
+/// World Tour worlds, along with the index of their first event
+const WORLD_TOUR_WORLDS: [(&str, usize); 6] = [
+    ("Sunshine Coast", 0),
+    ("Frozen Valley", 8),
+    ("Scorching Skies", 18),
+    ("Twilight Engine", 28),
+    ("Moonlight Park", 38),
+    ("Superstar Showdown", 48),
+];
+
+/// Offsets of the star values of each event inside the star block of a world
+const STAR_OFFSETS: [usize; 10] = [
+    0x7C, 0x138, 0x1F4, 0x2B0, 0x36C, 0x428, 0x4E4, 0x5A0, 0x65C, 0x718,
+];
+
+/// Index of Coastal Cruise, the first event of World Tour
+const COASTAL_CRUISE: usize = 0;
+/// Index of Canyon Carnage, the last event of Sunshine Coast
+const CANYON_CARNAGE: usize = 7;
+/// Indices of the events of Sunshine Coast, the first World Tour world
+const START_EVENTS_FIRST_WORLD: [usize; 8] = [0, 1, 2, 3, 4, 5, 6, 7];
+/// Index of Race of AGES in Moonlight Park, the last event of the Any% category
+const ARCADE_ANNIHILATION: usize = 47;
+/// Index of Race of AGES in Superstar Showdown, the last event of the 100% category
+const FATAL_FINALE: usize = 57;
+
+/// World Tour route presets, as event indices in the order they are expected
+const ROUTE_ANY_PERCENT: [usize; 48] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
+    26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47,
+];
+const ROUTE_ALL_EVENTS: [usize; WORLD_TOUR_EVENTS] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
+    26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49,
+    50, 51, 52, 53, 54, 55, 56, 57,
+];
+
+/// Display names of the World Tour events, in the order they appear in game
+const WORLD_TOUR_EVENT_NAMES: [&str; WORLD_TOUR_EVENTS] = [
+    "Coastal Cruise",
+    "Studio Scrapes",
+    "Battlezone Blast",
+    "Downtown Drift",
+    "Monkey Mayhem",
+    "Starry Speedway",
+    "Roulette Rush",
+    "Canyon Carnage",
+    "Snowball Shakedown",
+    "Banana Boost",
+    "Shinobi Scramble",
+    "Seaside Scrap",
+    "Tricky Traffic",
+    "Studio Scurry",
+    "Graffiti Groove",
+    "Shaking Skies",
+    "Neon Knockout",
+    "Pirate Plunder",
+    "Adder Assault",
+    "Dreamy Drive",
+    "Sanctuary Speedway",
+    "Keil's Carnage",
+    "Carrier Crisis",
+    "Sunshine Slide",
+    "Rogue Rings",
+    "Seaside Skirmish",
+    "Shrine Time",
+    "Hangar Hassle",
+    "Booty Boost",
+    "Racing Rangers",
+    "Shinobi Showdown",
+    "Ruin Run",
+    "Monkey Brawl",
+    "Crumbling Chaos",
+    "Hatcher Hustle",
+    "Death Egg Duel",
+    "Undertaker Overtaker",
+    "Golden Gauntlet",
+    "Carnival Clash",
+    "Curien Curves",
+    "Molten Mayhem",
+    "Speeding Seasons",
+    "Burning Boost",
+    "Ocean Outrun",
+    "Billy Backslide",
+    "Carrier Charge",
+    "Jet Set Jaunt",
+    "Arcade Annihilation",
+    "Rapid Ruins",
+    "Zombie Zoom",
+    "Maracar Madness",
+    "Nightmare Meander",
+    "Maraca Melee",
+    "Castle Chaos",
+    "Volcano Velocity",
+    "Ranger Rush",
+    "Tokyo Takeover",
+    "Fatal Finale",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the settings as they are registered when nothing has been changed
+    fn default_settings() -> Settings {
+        Settings {
+            _game: Title,
+            executable: Executable::Auto,
+            standby_on_stall: true,
+            _start: Title,
+            start: true,
+            start_on_race_active: false,
+            start_if_already_at_gate: false,
+            run_start_single_indicator: false,
+            wt_start_ignore_stars: false,
+            wt_start_events: WorldTourStartEvents::CoastalCruiseAndCanyonCarnage,
+            wt_start_any_world: false,
+            wt_start_only_unstarted_world: true,
+            _reset: Title,
+            reset: false,
+            reset_after_credits: false,
+            reset_grace_ms: ResetGracePeriod::ThreeSeconds,
+            _timing: Title,
+            override_game_time: true,
+            pause_on_igt_stall: false,
+            count_results_time: false,
+            single_race_use_final_time: false,
+            discard_retried_time: false,
+            gp_clean_race_time: false,
+            sync_on_midrun_attach: false,
+            credits_split_offset_ms: CreditsSplitOffset::Off,
+            split_timing: SplitTiming::AfterLoadChange,
+            _split_single: Title,
+            split_on_results_screen: false,
+            gp_split_on_cup_results: false,
+            gp_marathon: false,
+            gp_end_detection: false,
+            ocean_view: true,
+            samba_studios: true,
+            carrier_zone: true,
+            dragon_canyon: true,
+            temple_trouble: true,
+            galactic_parade: true,
+            seasonal_shrines: true,
+            rogues_landing: true,
+            dream_valley: true,
+            chilly_castle: true,
+            graffiti_city: true,
+            sanctuary_falls: true,
+            graveyard_gig: true,
+            adders_lair: true,
+            burning_depths: true,
+            race_of_ages: true,
+            sunshine_tour: true,
+            shibuya_downtown: true,
+            roulette_road: true,
+            egg_hangar: true,
+            outrun_bay: true,
+            _world_tour: Title,
+            wt_hundred_percent: false,
+            wt_end_source: WorldTourEndSource::Events,
+            wt_target_stars: TargetStars::Off,
+            wt_world_split: WorldTourWorldSplit::Off,
+            star_read_failure_policy: StarReadFailurePolicy::HoldLast,
+            wt_route: WorldTourRoute::Off,
+            arcade_split_mode: FinalEventSplitMode::Credits,
+            finale_split_mode: FinalEventSplitMode::Credits,
+            _sunshine_coast: Title,
+            coastal_cruise: true,
+            studio_scrapes: true,
+            battlezone_blast: true,
+            downtown_drift: true,
+            monkey_mayhem: true,
+            starry_speedway: true,
+            roulette_rush: true,
+            canyon_carnage: true,
+            _frozen_valley: Title,
+            snowball_shakedown: true,
+            banana_boost: true,
+            shinobi_scramble: true,
+            seaside_scrap: true,
+            tricky_traffic: true,
+            studio_scurry: true,
+            graffiti_groove: true,
+            shaking_skies: true,
+            neon_knockout: true,
+            pirate_plunder: true,
+            _scorching_skies: Title,
+            adder_assault: true,
+            dreamy_drive: true,
+            sanctuary_speedway: true,
+            keils_carnage: true,
+            carrier_crisis: true,
+            sunshine_slide: true,
+            rogue_rings: true,
+            seaside_skirmish: true,
+            shrine_time: true,
+            hangar_hassle: true,
+            _twilight_engine: Title,
+            booty_boost: true,
+            racing_rangers: true,
+            shinobi_showdown: true,
+            ruin_run: true,
+            monkey_brawl: true,
+            crumbling_chaos: true,
+            hatcher_hustle: true,
+            death_egg_duel: true,
+            undertaker_overtaker: true,
+            golden_gauntlet: true,
+            _moonlight_park: Title,
+            carnival_clash: true,
+            curien_curves: true,
+            molten_mayhem: true,
+            speeding_seasons: true,
+            burning_boost: true,
+            ocean_outrun: true,
+            billy_backslide: true,
+            carrier_charge: true,
+            jet_set_jaunt: true,
+            arcade_annihilation: true,
+            _superstar_showdown: Title,
+            rapid_ruins: true,
+            zombie_zoom: true,
+            maracar_madness: true,
+            nightmare_meander: true,
+            maraca_melee: true,
+            castle_chaos: true,
+            volcano_velocity: true,
+            ranger_rush: true,
+            tokyo_takeover: true,
+            fatal_finale: true,
+            _debug: Title,
+            debug_addresses: false,
+            export_state: false,
+        }
+    }
+
+    /// Sets both the previous and the current value of a watcher, as if it had been updated
+    /// twice from the game
+    fn set<T>(watcher: &mut Watcher<T>, old: T, current: T) {
+        watcher.pair = Some(Pair { old, current });
+    }
+
+    #[test]
+    fn watchers_can_be_set_directly() {
+        let settings = default_settings();
+        let mut watchers = Watchers::default();
+        set(
+            &mut watchers.game_mode,
+            GameMode::GandPrix,
+            GameMode::GandPrix,
+        );
+        set(&mut watchers.run_start, false, true);
+        assert!(start(&watchers, &settings));
+
+        set(
+            &mut watchers.game_mode,
+            GameMode::WorldTour,
+            GameMode::WorldTour,
+        );
+        set(&mut watchers.coastal_cruise, 0, 0);
+        set(&mut watchers.canyon_carnage, 0, 0);
+        assert!(start(&watchers, &settings));
+        assert!(!split(&watchers, &settings));
+
+        set(&mut watchers.coastal_cruise, 0, 1);
+        assert!(!split(&watchers, &settings));
+        watchers.game_mode_valid = true;
+        watchers.ticks_since_start = SPLIT_BUFFER_TICKS;
+        assert!(split(&watchers, &settings));
+    }
+
+    /// Returns watchers of a World Tour run in progress, past the split buffer
+    fn world_tour_watchers() -> Watchers {
+        let mut watchers = Watchers::default();
+        set(
+            &mut watchers.game_mode,
+            GameMode::WorldTour,
+            GameMode::WorldTour,
+        );
+        watchers.game_mode_valid = true;
+        watchers.ticks_since_start = SPLIT_BUFFER_TICKS;
+        watchers.star_blocks_read = true;
+        for event in watchers.world_tour_events_mut() {
+            set(event, 0, 0);
+        }
+        watchers
+    }
+
+    #[test]
+    fn world_tour_starts_on_a_fresh_save_only() {
+        let settings = default_settings();
+
+        let mut fresh = world_tour_watchers();
+        set(&mut fresh.run_start, false, true);
+        assert!(start(&fresh, &settings));
+
+        // The run only starts as the race is loaded
+        set(&mut fresh.run_start, true, true);
+        assert!(!start(&fresh, &settings));
+
+        let mut partial = world_tour_watchers();
+        set(&mut partial.run_start, false, true);
+        set(&mut partial.coastal_cruise, 2, 2);
+        assert!(!start(&partial, &settings));
+
+        let mut partial = world_tour_watchers();
+        set(&mut partial.run_start, false, true);
+        set(&mut partial.canyon_carnage, 2, 2);
+        assert!(!start(&partial, &settings));
+    }
+
+    #[test]
+    fn other_modes_start_regardless_of_the_stars() {
+        let settings = default_settings();
+        for (game_mode, starts) in [
+            (GameMode::GandPrix, true),
+            (GameMode::SingleRace, true),
+            (GameMode::TimeAttack, false),
+        ] {
+            let mut watchers = world_tour_watchers();
+            set(&mut watchers.game_mode, game_mode, game_mode);
+            set(&mut watchers.run_start, false, true);
+            set(&mut watchers.coastal_cruise, 4, 4);
+            assert_eq!(start(&watchers, &settings), starts);
+        }
+
+        let mut watchers = world_tour_watchers();
+        watchers.game_mode = Watcher::new();
+        set(&mut watchers.run_start, false, true);
+        assert!(!start(&watchers, &settings));
+    }
+
+    /// Returns the split setting of a track, so tests can toggle it
+    fn track_setting(settings: &mut Settings, track: Tracks) -> &mut bool {
+        match track {
+            Tracks::OceanView => &mut settings.ocean_view,
+            Tracks::SambaStudios => &mut settings.samba_studios,
+            Tracks::CarrierZone => &mut settings.carrier_zone,
+            Tracks::DragonCanyon => &mut settings.dragon_canyon,
+            Tracks::TempleTrouble => &mut settings.temple_trouble,
+            Tracks::GalacticParade => &mut settings.galactic_parade,
+            Tracks::SeasonalShrines => &mut settings.seasonal_shrines,
+            Tracks::RoguesLanding => &mut settings.rogues_landing,
+            Tracks::DreamValley => &mut settings.dream_valley,
+            Tracks::ChillyCastle => &mut settings.chilly_castle,
+            Tracks::GraffitiCity => &mut settings.graffiti_city,
+            Tracks::SanctuaryFalls => &mut settings.sanctuary_falls,
+            Tracks::GraveyardGig => &mut settings.graveyard_gig,
+            Tracks::AddersLair => &mut settings.adders_lair,
+            Tracks::BurningDepths => &mut settings.burning_depths,
+            Tracks::RaceOfAges => &mut settings.race_of_ages,
+            Tracks::SushineTour => &mut settings.sunshine_tour,
+            Tracks::ShibuyaDowntown => &mut settings.shibuya_downtown,
+            Tracks::RouletteRoad => &mut settings.roulette_road,
+            Tracks::EggHangar => &mut settings.egg_hangar,
+            Tracks::OutrunBay => &mut settings.outrun_bay,
+        }
+    }
+
+    #[test]
+    fn track_completions_split_on_their_own_setting() {
+        for &(_, track) in &TRACK_HASHES {
+            for game_mode in [GameMode::GandPrix, GameMode::SingleRace] {
+                let mut settings = default_settings();
+                for &(_, other) in &TRACK_HASHES {
+                    *track_setting(&mut settings, other) = other != track;
+                }
+
+                let mut watchers = Watchers::default();
+                set(&mut watchers.game_mode, game_mode, game_mode);
+                watchers.game_mode_valid = true;
+                watchers.ticks_since_start = SPLIT_BUFFER_TICKS;
+                set(&mut watchers.track_id, track, track);
+                set(&mut watchers.race_completed, false, true);
+                assert!(!split(&watchers, &settings));
+
+                *track_setting(&mut settings, track) = true;
+                assert!(split(&watchers, &settings));
+            }
+        }
+    }
+
+    #[test]
+    fn mode_switch_on_the_start_tick_is_taken_into_account() {
+        let settings = default_settings();
+
+        // Switching from World Tour to Grand Prix as the race loads starts a run,
+        // even though the World Tour save wouldn't allow one
+        let mut watchers = world_tour_watchers();
+        set(&mut watchers.coastal_cruise, 4, 4);
+        set(
+            &mut watchers.game_mode,
+            GameMode::WorldTour,
+            GameMode::GandPrix,
+        );
+        set(&mut watchers.run_start, false, true);
+        assert!(start(&watchers, &settings));
+
+        // Time Attack doesn't auto start, even when switched to on the start tick
+        set(
+            &mut watchers.game_mode,
+            GameMode::GandPrix,
+            GameMode::TimeAttack,
+        );
+        assert!(!start(&watchers, &settings));
+        set(
+            &mut watchers.game_mode,
+            GameMode::TimeAttack,
+            GameMode::WorldTour,
+        );
+        assert!(!start(&watchers, &settings));
+        set(&mut watchers.coastal_cruise, 4, 0);
+        assert!(start(&watchers, &settings));
+    }
+
+    #[test]
+    fn single_race_can_split_on_the_results_screen() {
+        let mut settings = default_settings();
+        settings.split_on_results_screen = true;
+        let mut watchers = Watchers::default();
+        set(
+            &mut watchers.game_mode,
+            GameMode::SingleRace,
+            GameMode::SingleRace,
+        );
+        watchers.game_mode_valid = true;
+        watchers.ticks_since_start = SPLIT_BUFFER_TICKS;
+        set(&mut watchers.track_id, Tracks::OceanView, Tracks::OceanView);
+
+        // Crossing the finish line doesn't split until race_status leaves the racing state
+        set(&mut watchers.race_completed, false, true);
+        set(&mut watchers.results_screen, false, false);
+        assert!(!split(&watchers, &settings));
+
+        set(&mut watchers.race_completed, true, true);
+        set(&mut watchers.results_screen, false, true);
+        assert!(split(&watchers, &settings));
+
+        set(&mut watchers.results_screen, true, true);
+        assert!(!split(&watchers, &settings));
+    }
+
+    #[test]
+    fn default_settings_always_report_game_time() {
+        let settings = default_settings();
+        assert!(settings.override_game_time);
+
+        let mut watchers = Watchers::default();
+        assert!(game_time(&watchers, &settings) == Some(Duration::ZERO));
+
+        watchers.progress_igt = Duration::seconds(60);
+        for game_mode in [
+            GameMode::WorldTour,
+            GameMode::GandPrix,
+            GameMode::SingleRace,
+            GameMode::TimeAttack,
+        ] {
+            set(&mut watchers.game_mode, game_mode, game_mode);
+            assert!(game_time(&watchers, &settings) == Some(Duration::seconds(60)));
+        }
+
+        // A completed single race without a final race time read yet
+        let mut settings = default_settings();
+        settings.single_race_use_final_time = true;
+        set(&mut watchers.race_completed, false, true);
+        set(
+            &mut watchers.game_mode,
+            GameMode::SingleRace,
+            GameMode::SingleRace,
+        );
+        assert!(game_time(&watchers, &settings) == Some(Duration::seconds(60)));
+
+        watchers.run_ended = true;
+        assert!(game_time(&watchers, &settings) == Some(Duration::seconds(60)));
+    }
+
+    /// Holds the current value of every star watcher, as if another tick had passed
+    fn hold_stars(watchers: &mut Watchers) {
+        for event in watchers.world_tour_events_mut() {
+            let stars = event.pair.unwrap_or_default().current;
+            set(event, stars, stars);
+        }
+    }
+
+    #[test]
+    fn route_splits_once_per_event_in_order() {
+        let mut settings = default_settings();
+        settings.wt_route = WorldTourRoute::AnyPercent;
+        let mut watchers = world_tour_watchers();
+
+        for (position, &event) in ROUTE_ANY_PERCENT[..4].iter().enumerate() {
+            set(watchers.world_tour_events_mut()[event], 0, 2);
+            assert!(auto_split(&mut watchers, &settings));
+            assert_eq!(watchers.route_position, position + 1);
+
+            hold_stars(&mut watchers);
+            assert!(!auto_split(&mut watchers, &settings));
+            assert_eq!(watchers.route_position, position + 1);
+        }
+        assert_eq!(watchers.auto_splits, 4);
+    }
+
+    #[test]
+    fn route_ignores_events_out_of_order() {
+        let mut settings = default_settings();
+        settings.wt_route = WorldTourRoute::AnyPercent;
+        let mut watchers = world_tour_watchers();
+        watchers.route_position = 4;
+
+        // An event further along the route, and one already passed
+        set(watchers.world_tour_events_mut()[ROUTE_ANY_PERCENT[6]], 0, 1);
+        assert!(!auto_split(&mut watchers, &settings));
+        hold_stars(&mut watchers);
+        set(watchers.world_tour_events_mut()[ROUTE_ANY_PERCENT[1]], 0, 3);
+        assert!(!auto_split(&mut watchers, &settings));
+        assert_eq!(watchers.route_position, 4);
+
+        hold_stars(&mut watchers);
+        set(watchers.world_tour_events_mut()[ROUTE_ANY_PERCENT[4]], 0, 1);
+        assert!(auto_split(&mut watchers, &settings));
+        assert_eq!(watchers.route_position, 5);
+        assert_eq!(watchers.auto_splits, 1);
+    }
+
+    #[test]
+    fn game_time_is_frozen_once_the_run_has_ended() {
+        let mut settings = default_settings();
+        settings.single_race_use_final_time = true;
+        settings.credits_split_offset_ms = CreditsSplitOffset::PlusOneSecond;
+        let mut watchers = Watchers {
+            progress_igt: Duration::minutes(30),
+            run_ended: true,
+            ..Default::default()
+        };
+        let final_time = Some(Duration::minutes(30));
+
+        // None of the per-mode sources are used past the end of the run, whatever the game
+        // reports while the player wanders the menus
+        set(
+            &mut watchers.game_mode,
+            GameMode::SingleRace,
+            GameMode::SingleRace,
+        );
+        set(&mut watchers.race_completed, false, true);
+        set(
+            &mut watchers.total_race_time,
+            Duration::ZERO,
+            Duration::seconds(90),
+        );
+        assert!(game_time(&watchers, &settings) == final_time);
+
+        set(
+            &mut watchers.game_mode,
+            GameMode::WorldTour,
+            GameMode::WorldTour,
+        );
+        set(&mut watchers.end_credits, false, true);
+        assert!(game_time(&watchers, &settings) == final_time);
+    }
+
+    #[test]
+    fn route_presets_list_events_in_game_order() {
+        assert!(WorldTourRoute::Off.events().is_empty());
+        for route in [WorldTourRoute::AnyPercent, WorldTourRoute::AllEvents] {
+            let events = route.events();
+            assert_eq!(events[0], COASTAL_CRUISE);
+            assert!(events.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+        assert_eq!(
+            WorldTourRoute::AnyPercent.events().last(),
+            Some(&ARCADE_ANNIHILATION)
+        );
+        assert_eq!(WorldTourRoute::AllEvents.events().len(), WORLD_TOUR_EVENTS);
+        assert_eq!(
+            WorldTourRoute::AllEvents.events().last(),
+            Some(&FATAL_FINALE)
+        );
+    }
+
+    #[test]
+    fn reset_grace_periods() {
+        assert_eq!(ResetGracePeriod::Off.milliseconds(), 0);
+        assert_eq!(ResetGracePeriod::OneSecond.milliseconds(), 1_000);
+        assert_eq!(ResetGracePeriod::ThreeSeconds.milliseconds(), 3_000);
+        assert_eq!(ResetGracePeriod::FiveSeconds.milliseconds(), 5_000);
+        assert_eq!(ResetGracePeriod::TenSeconds.milliseconds(), 10_000);
+        assert_eq!(default_settings().reset_grace_ms.milliseconds(), 3_000);
+    }
+
+    #[test]
+    fn races_timed_by_igt() {
+        // World Tour events without laps (e.g. battles) and IGT timed events use the IGT
+        assert!(race_timed_by_igt(
+            Some(GameMode::WorldTour),
+            REQUIRED_LAPS_NONE,
+            None
+        ));
+        assert!(race_timed_by_igt(
+            Some(GameMode::GandPrix),
+            3,
+            Some(EVENT_TYPE_IGT_TIMED)
+        ));
+        // Regular races use the total race time
+        assert!(!race_timed_by_igt(Some(GameMode::WorldTour), 3, None));
+        assert!(!race_timed_by_igt(
+            Some(GameMode::GandPrix),
+            REQUIRED_LAPS_NONE,
+            None
+        ));
+        assert!(!race_timed_by_igt(None, 3, Some(0)));
+    }
+
+    #[test]
+    fn derived_state_is_computed_from_the_watchers() {
+        let mut watchers = Watchers::default();
+        set(&mut watchers.race_completed, false, true);
+        set(&mut watchers.race_status, RACE_STATUS_RACING, 0);
+        update_state(&mut watchers, &default_settings(), TimerState::Running);
+        assert!(watchers
+            .results_screen
+            .pair
+            .is_some_and(|value| value.current));
+    }
+
+    #[test]
+    fn race_times_from_secs() {
+        assert_eq!(race_time_from_secs(0.0), Some(Duration::ZERO));
+        // Truncated to centiseconds
+        assert_eq!(
+            race_time_from_secs(83.456),
+            Some(Duration::milliseconds(83_450))
+        );
+        assert_eq!(race_time_from_secs(-0.5), None);
+        assert_eq!(race_time_from_secs(f32::NAN), None);
+        assert_eq!(race_time_from_secs(f32::INFINITY), None);
+        assert_eq!(race_time_from_secs(f32::NEG_INFINITY), None);
+        // A huge value read from stale memory would overflow the conversion
+        assert_eq!(race_time_from_secs(1e30), None);
+        assert_eq!(race_time_from_secs(f32::MAX), None);
+    }
+
+    #[test]
+    fn negative_race_time_keeps_the_accumulated_time() {
+        let settings = default_settings();
+        let mut watchers = Watchers::default();
+        set(
+            &mut watchers.game_mode,
+            GameMode::GandPrix,
+            GameMode::GandPrix,
+        );
+        set(
+            &mut watchers.race_status,
+            RACE_STATUS_RACING,
+            RACE_STATUS_RACING,
+        );
+        set(
+            &mut watchers.igt,
+            Duration::seconds(60),
+            Duration::seconds(61),
+        );
+        set(&mut watchers.race_completed, false, true);
+        set(&mut watchers.required_laps, 3, 3);
+
+        set(
+            &mut watchers.total_race_time,
+            Duration::seconds(61),
+            Duration::seconds(61),
+        );
+        watchers.total_igt = Duration::seconds(120);
+        watchers.progress_igt = Duration::seconds(181);
+
+        // A negative read is rejected, so the last valid total race time is accumulated
+        watchers.total_race_time_secs = Some(-20.0);
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert_eq!(
+            watchers.total_race_time.pair.map(|value| value.current),
+            Some(Duration::seconds(61))
+        );
+        assert_eq!(watchers.total_igt, Duration::seconds(181));
+        assert_eq!(watchers.progress_igt, Duration::seconds(181));
+
+        // Further negative reads on the results screen leave the accumulated time alone
+        set(&mut watchers.race_completed, true, true);
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert_eq!(watchers.total_igt, Duration::seconds(181));
+        assert_eq!(watchers.progress_igt, Duration::seconds(181));
+    }
+
+    #[test]
+    fn first_loading_state_is_applied_right_away() {
+        let (state, action) = loading_action(LoadingState::default(), true);
+        assert!(action == Some(LoadingAction::Pause));
+        assert_eq!(state.applied, Some(true));
+
+        let (_, action) = loading_action(LoadingState::default(), false);
+        assert!(action == Some(LoadingAction::Resume));
+    }
+
+    #[test]
+    fn loading_state_change_holds_before_being_applied() {
+        let mut state = LoadingState::default();
+        state.force(true);
+        for _ in 1..LOADING_DEBOUNCE_TICKS {
+            assert!(state.update(false).is_none());
+            assert_eq!(state.applied, Some(true));
+        }
+        assert!(state.update(false) == Some(LoadingAction::Resume));
+        assert!(state.update(false).is_none());
+    }
+
+    #[test]
+    fn loading_state_flipping_back_is_ignored() {
+        let mut state = LoadingState::default();
+        state.force(false);
+        for _ in 0..10 {
+            for _ in 1..LOADING_DEBOUNCE_TICKS {
+                assert!(state.update(true).is_none());
+            }
+            // Going back to the applied state before the threshold restarts the hold
+            assert!(state.update(false).is_none());
+            assert_eq!(state.pending_ticks, 0);
+        }
+        assert_eq!(state.applied, Some(false));
+    }
+
+    #[test]
+    fn simultaneous_star_increases_split_once_each() {
+        let settings = default_settings();
+        let mut watchers = world_tour_watchers();
+        set(&mut watchers.coastal_cruise, 0, 1);
+        set(&mut watchers.studio_scrapes, 0, 2);
+
+        assert!(auto_split(&mut watchers, &settings));
+        hold_stars(&mut watchers);
+        assert!(auto_split(&mut watchers, &settings));
+        hold_stars(&mut watchers);
+        assert!(!auto_split(&mut watchers, &settings));
+        assert_eq!(watchers.auto_splits, 2);
+    }
+
+    #[test]
+    fn queued_splits_add_up() {
+        let settings = default_settings();
+        let mut watchers = world_tour_watchers();
+        set(&mut watchers.coastal_cruise, 0, 1);
+        set(&mut watchers.studio_scrapes, 0, 1);
+        set(&mut watchers.battlezone_blast, 0, 1);
+        assert!(auto_split(&mut watchers, &settings));
+        assert_eq!(watchers.pending_splits, 2);
+
+        // Another pair of events completed while splits are still queued
+        hold_stars(&mut watchers);
+        set(&mut watchers.downtown_drift, 0, 1);
+        set(&mut watchers.monkey_mayhem, 0, 1);
+        assert!(auto_split(&mut watchers, &settings));
+        assert_eq!(watchers.pending_splits, 3);
+
+        hold_stars(&mut watchers);
+        let mut splits = 0;
+        while auto_split(&mut watchers, &settings) {
+            splits += 1;
+        }
+        assert_eq!(splits, 3);
+        assert_eq!(watchers.auto_splits, 5);
+    }
+
+    #[test]
+    fn queued_splits_wait_for_splitting_to_be_allowed() {
+        let settings = default_settings();
+        let mut watchers = world_tour_watchers();
+        set(&mut watchers.coastal_cruise, 0, 1);
+        set(&mut watchers.studio_scrapes, 0, 1);
+        assert!(auto_split(&mut watchers, &settings));
+
+        hold_stars(&mut watchers);
+        watchers.ticks_since_start = 0;
+        assert!(!auto_split(&mut watchers, &settings));
+        assert_eq!(watchers.pending_splits, 1);
+
+        watchers.ticks_since_start = SPLIT_BUFFER_TICKS;
+        watchers.run_ended = true;
+        assert!(!auto_split(&mut watchers, &settings));
+    }
+
+    #[test]
+    fn route_advances_only_on_splits() {
+        let mut settings = default_settings();
+        settings.wt_route = WorldTourRoute::AnyPercent;
+        let mut watchers = world_tour_watchers();
+
+        // The split buffer suppresses the split, so the route keeps expecting the event
+        set(&mut watchers.coastal_cruise, 0, 1);
+        watchers.ticks_since_start = 0;
+        assert!(!auto_split(&mut watchers, &settings));
+        assert_eq!(watchers.route_position, 0);
+
+        watchers.ticks_since_start = SPLIT_BUFFER_TICKS;
+        assert!(auto_split(&mut watchers, &settings));
+        assert_eq!(watchers.route_position, 1);
+        assert_eq!(next_split(&watchers, &settings), Some("Studio Scrapes"));
+    }
+
+    #[test]
+    fn route_skips_events_with_disabled_splits() {
+        let mut settings = default_settings();
+        settings.wt_route = WorldTourRoute::AnyPercent;
+        settings.studio_scrapes = false;
+        let mut watchers = world_tour_watchers();
+        watchers.route_position = 1;
+
+        assert_eq!(next_split(&watchers, &settings), Some("Battlezone Blast"));
+        set(&mut watchers.battlezone_blast, 0, 1);
+        assert!(auto_split(&mut watchers, &settings));
+        assert_eq!(watchers.route_position, 3);
+    }
+
+    #[test]
+    fn main_module_sizes() {
+        assert_eq!(
+            main_module_size(Some(0x1_000_000), Some(0x2_000)),
+            0x1_000_000
+        );
+        // A bogus header falls back to the size reported by the runtime
+        assert_eq!(main_module_size(Some(0), Some(0x1_000_000)), 0x1_000_000);
+        assert_eq!(main_module_size(None, Some(0x1_000_000)), 0x1_000_000);
+        // Neither fits a 32-bit image, so the default window is scanned instead
+        assert_eq!(
+            main_module_size(Some(0), Some(1 << 40)),
+            DEFAULT_MODULE_SIZE
+        );
+        assert_eq!(main_module_size(None, None), DEFAULT_MODULE_SIZE);
+    }
+
+    #[test]
+    fn credits_offset_is_only_applied_to_the_end_of_the_run() {
+        let mut settings = default_settings();
+        settings.credits_split_offset_ms = CreditsSplitOffset::MinusOneSecond;
+        settings.wt_end_source = WorldTourEndSource::CreditsFlag;
+        let mut watchers = world_tour_watchers();
+        set(&mut watchers.race_completed, true, true);
+        set(&mut watchers.race_status, 0, 0);
+        set(
+            &mut watchers.igt,
+            Duration::seconds(90),
+            Duration::seconds(90),
+        );
+        watchers.total_igt = Duration::seconds(600);
+        watchers.progress_igt = Duration::seconds(600);
+
+        set(&mut watchers.end_credits, false, true);
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert_eq!(watchers.progress_igt, Duration::seconds(600));
+        assert!(auto_split(&mut watchers, &settings));
+        assert_eq!(
+            game_time(&watchers, &settings),
+            Some(Duration::seconds(599))
+        );
+
+        // The accumulated time isn't shifted, so a new run doesn't carry the offset over
+        assert_eq!(watchers.total_igt, Duration::seconds(600));
+        update_state(&mut watchers, &settings, TimerState::NotRunning);
+        assert_eq!(watchers.credits_igt, None);
+    }
+
+    #[test]
+    fn passthrough_timing_doesnt_pause_after_the_start() {
+        let mut settings = default_settings();
+        settings.override_game_time = false;
+        let mut watchers = Watchers::default();
+        set(&mut watchers.race_status, 0, 0);
+
+        // Not racing yet, which counts as loading without the in-game time overriding it
+        let is_loading = is_loading(&watchers, &settings).unwrap();
+        assert!(is_loading);
+
+        let mut state = LoadingState::default();
+        assert!(state
+            .start(is_loading, settings.override_game_time)
+            .is_none());
+        for _ in 0..LOADING_DEBOUNCE_TICKS * 2 {
+            assert!(state.update(is_loading).is_none());
+        }
+    }
+
+    #[test]
+    fn start_pauses_game_time_once_while_loading() {
+        let mut state = LoadingState::default();
+        assert!(state.start(true, true) == Some(LoadingAction::Pause));
+        // Still loading: no pause, resume, pause flicker
+        for _ in 0..LOADING_DEBOUNCE_TICKS * 2 {
+            assert!(state.update(true).is_none());
+        }
+        let actions: usize = (0..LOADING_DEBOUNCE_TICKS * 2)
+            .filter(|_| state.update(false) == Some(LoadingAction::Resume))
+            .count();
+        assert_eq!(actions, 1);
+    }
+
+    #[test]
+    fn start_doesnt_touch_game_time_when_not_loading() {
+        let mut state = LoadingState::default();
+        assert!(state.start(false, true).is_none());
+        assert!(state.update(false).is_none());
+    }
+
+    #[test]
+    fn world_is_unknown_until_a_star_block_is_read() {
+        let settings = default_settings();
+        let mut watchers = world_tour_watchers();
+        watchers.star_blocks_read = false;
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert!(watchers.current_world.pair.is_none());
+        assert_eq!(watchers.furthest_world, None);
+
+        watchers.star_blocks_read = true;
+        for event in &mut watchers.world_tour_events_mut()[..8] {
+            set(event, 4, 4);
+        }
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert_eq!(
+            watchers.current_world.pair.map(|value| value.current),
+            Some(1)
+        );
+        assert_eq!(watchers.furthest_world, Some(1));
+    }
+
+    /// Returns watchers of a Grand Prix race being run
+    fn racing_watchers() -> Watchers {
+        let mut watchers = Watchers::default();
+        set(
+            &mut watchers.game_mode,
+            GameMode::GandPrix,
+            GameMode::GandPrix,
+        );
+        set(&mut watchers.run_start, true, true);
+        set(&mut watchers.race_completed, false, false);
+        set(
+            &mut watchers.race_status,
+            RACE_STATUS_RACING,
+            RACE_STATUS_RACING,
+        );
+        watchers
+    }
+
+    #[test]
+    fn unloading_a_running_race_is_a_quit() {
+        let settings = default_settings();
+        let mut watchers = racing_watchers();
+        update_state(&mut watchers, &settings, TimerState::Running);
+
+        // The pause menu moves the race status away from racing before the race is unloaded
+        set(&mut watchers.race_status, RACE_STATUS_RACING, 0);
+        update_state(&mut watchers, &settings, TimerState::Running);
+        set(&mut watchers.race_status, 0, 0);
+        set(&mut watchers.run_start, true, false);
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert!(watchers
+            .race_exit
+            .pair
+            .is_some_and(|value| value.current == RaceExit::Quit));
+    }
+
+    #[test]
+    fn unloading_a_race_before_it_runs_isnt_a_quit() {
+        let settings = default_settings();
+        let mut watchers = racing_watchers();
+        set(&mut watchers.race_status, 0, 0);
+        update_state(&mut watchers, &settings, TimerState::Running);
+
+        set(&mut watchers.run_start, true, false);
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert!(watchers
+            .race_exit
+            .pair
+            .is_some_and(|value| value.current == RaceExit::None));
+        assert_eq!(watchers.quit_pending_ticks, None);
+    }
+
+    #[test]
+    fn flickering_race_unload_doesnt_reset() {
+        let mut settings = default_settings();
+        settings.reset = true;
+        let mut watchers = racing_watchers();
+        watchers.ticks_since_timer_start = 5 * 60 * DEFAULT_TICK_RATE as u32;
+        update_state(&mut watchers, &settings, TimerState::Running);
+
+        set(&mut watchers.race_status, RACE_STATUS_RACING, 0);
+        set(&mut watchers.run_start, true, false);
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert_eq!(watchers.quit_pending_ticks, Some(0));
+
+        set(&mut watchers.run_start, false, true);
+        for _ in 0..RESET_CONFIRMATION_TICKS {
+            update_state(&mut watchers, &settings, TimerState::Running);
+            assert!(!reset(&watchers, &settings, TimerState::Running));
+            set(&mut watchers.run_start, true, true);
+        }
+        assert_eq!(watchers.quit_pending_ticks, None);
+    }
+
+    #[test]
+    fn confirmed_quit_resets() {
+        let mut settings = default_settings();
+        settings.reset = true;
+        let mut watchers = racing_watchers();
+        watchers.ticks_since_timer_start = 5 * 60 * DEFAULT_TICK_RATE as u32;
+        update_state(&mut watchers, &settings, TimerState::Running);
+
+        set(&mut watchers.race_status, RACE_STATUS_RACING, 0);
+        set(&mut watchers.run_start, true, false);
+        for _ in 0..RESET_CONFIRMATION_TICKS {
+            update_state(&mut watchers, &settings, TimerState::Running);
+            assert!(!reset(&watchers, &settings, TimerState::Running));
+            set(&mut watchers.race_status, 0, 0);
+            set(&mut watchers.run_start, false, false);
+        }
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert!(reset(&watchers, &settings, TimerState::Running));
+    }
+
+    /// Completes a race on the given track, as of the current tick
+    fn complete_race(watchers: &mut Watchers, settings: &Settings, track: Tracks) {
+        set(&mut watchers.track_id, track, track);
+        set(&mut watchers.race_completed, false, true);
+        update_state(watchers, settings, TimerState::Running);
+    }
+
+    #[test]
+    fn marathon_only_splits_on_enabled_tracks() {
+        let mut settings = default_settings();
+        settings.gp_marathon = true;
+        settings.ocean_view = false;
+        let mut watchers = racing_watchers();
+        watchers.game_mode_valid = true;
+        watchers.ticks_since_start = SPLIT_BUFFER_TICKS;
+        update_state(&mut watchers, &settings, TimerState::Running);
+
+        complete_race(&mut watchers, &settings, Tracks::OceanView);
+        assert!(watchers
+            .completed_tracks
+            .pair
+            .is_some_and(|value| value.changed()));
+        assert!(!split(&watchers, &settings));
+
+        complete_race(&mut watchers, &settings, Tracks::SambaStudios);
+        assert!(split(&watchers, &settings));
+
+        // Completing a track again doesn't split
+        complete_race(&mut watchers, &settings, Tracks::SambaStudios);
+        assert!(!split(&watchers, &settings));
+    }
+
+    #[test]
+    fn marathon_needs_an_enabled_track() {
+        let mut settings = default_settings();
+        settings.gp_marathon = true;
+        for &(_, track) in &TRACK_HASHES {
+            *track_setting(&mut settings, track) = false;
+        }
+        assert!(!settings.splits_enabled(GameMode::GandPrix));
+        assert!(!settings.splits_enabled(GameMode::SingleRace));
+        *track_setting(&mut settings, Tracks::OceanView) = true;
+        assert!(settings.splits_enabled(GameMode::GandPrix));
+        assert!(settings.splits_enabled(GameMode::SingleRace));
+    }
+
+    /// Returns watchers midway through a first playthrough, back in an earlier world
+    fn first_playthrough_watchers(settings: &Settings) -> Watchers {
+        let mut watchers = world_tour_watchers();
+        for event in &mut watchers.world_tour_events_mut()[..20] {
+            set(event, 3, 3);
+        }
+        update_state(&mut watchers, settings, TimerState::Running);
+        set(&mut watchers.coastal_cruise, 3, 4);
+        update_state(&mut watchers, settings, TimerState::Running);
+        watchers
+    }
+
+    #[test]
+    fn replays_are_told_from_first_playthroughs() {
+        let settings = default_settings();
+        let mut watchers = world_tour_watchers();
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert!(!world_tour_replay(&watchers));
+        let watchers = first_playthrough_watchers(&settings);
+        assert!(!world_tour_replay(&watchers));
+        assert_eq!(watchers.furthest_world, Some(2));
+
+        let mut replay = first_playthrough_watchers(&settings);
+        set(replay.world_tour_events_mut()[ARCADE_ANNIHILATION], 0, 1);
+        update_state(&mut replay, &settings, TimerState::Running);
+        assert!(world_tour_replay(&replay));
+
+        let mut replay = first_playthrough_watchers(&settings);
+        set(replay.world_tour_events_mut()[FATAL_FINALE], 0, 2);
+        update_state(&mut replay, &settings, TimerState::Running);
+        assert!(world_tour_replay(&replay));
+
+        let mut replay = first_playthrough_watchers(&settings);
+        replay.furthest_world = Some(WORLD_TOUR_WORLDS.len() - 1);
+        assert!(world_tour_replay(&replay));
+    }
+
+    #[test]
+    fn durations_are_formatted_like_the_game() {
+        let format = |time, format| format_duration(time, format).as_str().to_owned();
+        assert_eq!(format(Duration::ZERO, DurationFormat::RaceClock), "0:00.00");
+        assert_eq!(format(Duration::ZERO, DurationFormat::RunClock), "0:00.00");
+        // Times are truncated to the centisecond, like the in-game clock
+        assert_eq!(
+            format(Duration::milliseconds(59_995), DurationFormat::RaceClock),
+            "0:59.99"
+        );
+        assert_eq!(
+            format(Duration::milliseconds(83_456), DurationFormat::RaceClock),
+            "1:23.45"
+        );
+        assert_eq!(
+            format(Duration::hours(1), DurationFormat::RaceClock),
+            "60:00.00"
+        );
+        assert_eq!(
+            format(Duration::hours(1), DurationFormat::RunClock),
+            "1:00:00.00"
+        );
+        assert_eq!(
+            format(
+                Duration::hours(1) - Duration::milliseconds(10),
+                DurationFormat::RunClock
+            ),
+            "59:59.99"
+        );
+        assert_eq!(
+            format(-Duration::milliseconds(1_500), DurationFormat::RaceClock),
+            "-0:01.50"
+        );
+        assert_eq!(
+            format(-Duration::minutes(61), DurationFormat::RunClock),
+            "-1:01:00.00"
+        );
+    }
+
+    #[test]
+    fn every_cup_position_holds_one_track() {
+        let mut positions = [[0; 5]; CUP_NAMES.len()];
+        for &(_, track) in &TRACK_HASHES {
+            let (cup, position) = track.cup_and_position();
+            positions[cup][position] += 1;
+        }
+        for (cup, positions) in positions.iter().enumerate() {
+            assert_eq!(positions[..CUP_SIZES[cup]], [1; 5][..CUP_SIZES[cup]]);
+            assert!(positions[CUP_SIZES[cup]..]
+                .iter()
+                .all(|&tracks| tracks == 0));
+        }
+        assert!(Tracks::RaceOfAges.last_in_cup());
+        assert!(!Tracks::EggHangar.last_in_cup());
+        assert!(Tracks::OutrunBay.last_in_cup());
+    }
+
+    #[test]
+    fn track_hashes_map_to_their_track() {
+        // The hashes matched by the original script, in the order of the track settings
+        let known = [
+            (0xD4257EBD, Tracks::OceanView),
+            (0x32D305A8, Tracks::SambaStudios),
+            (0xC72B3B98, Tracks::CarrierZone),
+            (0x03EB7FFF, Tracks::DragonCanyon),
+            (0xE3121777, Tracks::TempleTrouble),
+            (0x4E015AB6, Tracks::GalacticParade),
+            (0x503C1CBC, Tracks::SeasonalShrines),
+            (0x7534B7CA, Tracks::RoguesLanding),
+            (0x38A394ED, Tracks::DreamValley),
+            (0xC5C9DEA1, Tracks::ChillyCastle),
+            (0xD936550C, Tracks::GraffitiCity),
+            (0x4A0FF7AE, Tracks::SanctuaryFalls),
+            (0xCD8017BA, Tracks::GraveyardGig),
+            (0xDC93F18B, Tracks::AddersLair),
+            (0x2DB91FC2, Tracks::BurningDepths),
+            (0x94610644, Tracks::RaceOfAges),
+            (0xE6CD97F0, Tracks::SushineTour),
+            (0xE87FDF22, Tracks::ShibuyaDowntown),
+            (0x17463C8D, Tracks::RouletteRoad),
+            (0xFEBC639E, Tracks::EggHangar),
+            (0x1EF56CE1, Tracks::OutrunBay),
+        ];
+        for (hash, track) in known {
+            assert!(Tracks::from_hash(hash) == Some(track));
+        }
+        assert!(Tracks::from_hash(0).is_none());
+        assert!(Tracks::from_hash(EVENT_TYPE_IGT_TIMED).is_none());
+
+        for (i, &(hash, track)) in TRACK_HASHES.iter().enumerate() {
+            assert!(TRACK_HASHES[i + 1..]
+                .iter()
+                .all(|&(other_hash, other_track)| other_hash != hash && other_track != track));
+        }
+    }
+
+    /// Loads the next race and runs it until the race timer reaches the given time
+    fn start_race(watchers: &mut Watchers, settings: &Settings, igt: Duration) {
+        let completed = watchers
+            .race_completed
+            .pair
+            .is_some_and(|value| value.current);
+        set(&mut watchers.race_completed, completed, false);
+        set(&mut watchers.igt, Duration::ZERO, Duration::ZERO);
+        set(&mut watchers.race_status, 0, RACE_STATUS_RACING);
+        update_state(watchers, settings, TimerState::Running);
+
+        set(
+            &mut watchers.race_status,
+            RACE_STATUS_RACING,
+            RACE_STATUS_RACING,
+        );
+        set(&mut watchers.igt, Duration::ZERO, igt);
+        update_state(watchers, settings, TimerState::Running);
+    }
+
+    /// Plays a whole race, from its countdown to its completion, as timed by the game
+    fn play_race(watchers: &mut Watchers, settings: &Settings, igt: Duration, race_time: Duration) {
+        start_race(watchers, settings, igt);
+
+        set(&mut watchers.igt, igt, igt);
+        set(&mut watchers.total_race_time, Duration::ZERO, race_time);
+        set(&mut watchers.race_completed, false, true);
+        update_state(watchers, settings, TimerState::Running);
+
+        set(&mut watchers.race_status, RACE_STATUS_RACING, 0);
+        set(&mut watchers.race_completed, true, true);
+        update_state(watchers, settings, TimerState::Running);
+    }
+
+    #[test]
+    fn clean_grand_prix_races_add_up() {
+        let mut settings = default_settings();
+        settings.gp_clean_race_time = true;
+        let mut watchers = racing_watchers();
+        set(&mut watchers.required_laps, 3, 3);
+
+        play_race(
+            &mut watchers,
+            &settings,
+            Duration::seconds(82),
+            Duration::milliseconds(80_250),
+        );
+        assert_eq!(watchers.progress_igt, Duration::milliseconds(80_250));
+
+        // The time spent in the race doesn't show up before it's completed
+        start_race(&mut watchers, &settings, Duration::seconds(30));
+        assert_eq!(watchers.progress_igt, Duration::milliseconds(80_250));
+
+        play_race(
+            &mut watchers,
+            &settings,
+            Duration::seconds(77),
+            Duration::milliseconds(75_500),
+        );
+        assert_eq!(watchers.total_igt, Duration::milliseconds(155_750));
+        assert_eq!(watchers.progress_igt, Duration::milliseconds(155_750));
+    }
+
+    #[test]
+    fn race_completion_toggling_is_accumulated_once() {
+        let settings = default_settings();
+        let mut watchers = racing_watchers();
+        set(&mut watchers.required_laps, 3, 3);
+        play_race(
+            &mut watchers,
+            &settings,
+            Duration::seconds(82),
+            Duration::seconds(80),
+        );
+        assert_eq!(watchers.total_igt, Duration::seconds(80));
+
+        // The results reload without another race being started
+        for _ in 0..2 {
+            set(&mut watchers.race_completed, true, false);
+            update_state(&mut watchers, &settings, TimerState::Running);
+            set(&mut watchers.race_completed, false, true);
+            update_state(&mut watchers, &settings, TimerState::Running);
+            assert_eq!(watchers.total_igt, Duration::seconds(80));
+            assert_eq!(watchers.progress_igt, Duration::seconds(80));
+        }
+
+        play_race(
+            &mut watchers,
+            &settings,
+            Duration::seconds(62),
+            Duration::seconds(60),
+        );
+        assert_eq!(watchers.total_igt, Duration::seconds(140));
+    }
+
+    /// Disables splitting on every World Tour event
+    fn disable_world_tour_events(settings: &mut Settings) {
+        settings.coastal_cruise = false;
+        settings.studio_scrapes = false;
+        settings.battlezone_blast = false;
+        settings.downtown_drift = false;
+        settings.monkey_mayhem = false;
+        settings.starry_speedway = false;
+        settings.roulette_rush = false;
+        settings.canyon_carnage = false;
+        settings.snowball_shakedown = false;
+        settings.banana_boost = false;
+        settings.shinobi_scramble = false;
+        settings.seaside_scrap = false;
+        settings.tricky_traffic = false;
+        settings.studio_scurry = false;
+        settings.graffiti_groove = false;
+        settings.shaking_skies = false;
+        settings.neon_knockout = false;
+        settings.pirate_plunder = false;
+        settings.adder_assault = false;
+        settings.dreamy_drive = false;
+        settings.sanctuary_speedway = false;
+        settings.keils_carnage = false;
+        settings.carrier_crisis = false;
+        settings.sunshine_slide = false;
+        settings.rogue_rings = false;
+        settings.seaside_skirmish = false;
+        settings.shrine_time = false;
+        settings.hangar_hassle = false;
+        settings.booty_boost = false;
+        settings.racing_rangers = false;
+        settings.shinobi_showdown = false;
+        settings.ruin_run = false;
+        settings.monkey_brawl = false;
+        settings.crumbling_chaos = false;
+        settings.hatcher_hustle = false;
+        settings.death_egg_duel = false;
+        settings.undertaker_overtaker = false;
+        settings.golden_gauntlet = false;
+        settings.carnival_clash = false;
+        settings.curien_curves = false;
+        settings.molten_mayhem = false;
+        settings.speeding_seasons = false;
+        settings.burning_boost = false;
+        settings.ocean_outrun = false;
+        settings.billy_backslide = false;
+        settings.carrier_charge = false;
+        settings.jet_set_jaunt = false;
+        settings.arcade_annihilation = false;
+        settings.rapid_ruins = false;
+        settings.zombie_zoom = false;
+        settings.maracar_madness = false;
+        settings.nightmare_meander = false;
+        settings.maraca_melee = false;
+        settings.castle_chaos = false;
+        settings.volcano_velocity = false;
+        settings.ranger_rush = false;
+        settings.tokyo_takeover = false;
+        settings.fatal_finale = false;
+    }
+
+    #[test]
+    fn nothing_splits_with_every_split_disabled() {
+        let mut settings = default_settings();
+        disable_world_tour_events(&mut settings);
+        for &(_, track) in &TRACK_HASHES {
+            *track_setting(&mut settings, track) = false;
+        }
+        for game_mode in [
+            GameMode::WorldTour,
+            GameMode::GandPrix,
+            GameMode::SingleRace,
+            GameMode::TimeAttack,
+        ] {
+            assert!(!settings.splits_enabled(game_mode));
+        }
+
+        let mut watchers = world_tour_watchers();
+        set(&mut watchers.coastal_cruise, 0, 3);
+        assert!(!split(&watchers, &settings));
+        assert!(!auto_split(&mut watchers, &settings));
+
+        let mut watchers = racing_watchers();
+        watchers.game_mode_valid = true;
+        watchers.ticks_since_start = SPLIT_BUFFER_TICKS;
+        update_state(&mut watchers, &settings, TimerState::Running);
+        complete_race(&mut watchers, &settings, Tracks::OceanView);
+        assert!(!split(&watchers, &settings));
+        assert!(!auto_split(&mut watchers, &settings));
+    }
+
+    #[test]
+    fn zero_event_type_keeps_the_last_one() {
+        let settings = default_settings();
+        let mut watchers = racing_watchers();
+        set(&mut watchers.required_laps, 3, 3);
+        watchers.event_type.update_infallible(event_type_or_last(
+            Some(EVENT_TYPE_IGT_TIMED),
+            watchers.event_type.pair,
+        ));
+        for event_type in [Some(0), None, Some(0)] {
+            watchers
+                .event_type
+                .update_infallible(event_type_or_last(event_type, watchers.event_type.pair));
+            assert!(watchers
+                .event_type
+                .pair
+                .is_some_and(|value| value.current == EVENT_TYPE_IGT_TIMED));
+        }
+
+        // The race is still timed with the in-game timer
+        play_race(
+            &mut watchers,
+            &settings,
+            Duration::seconds(45),
+            Duration::seconds(90),
+        );
+        assert_eq!(watchers.total_igt, Duration::seconds(45));
+
+        assert_eq!(event_type_or_last(Some(0), None), 0);
+        assert_eq!(event_type_or_last(Some(1), watchers.event_type.pair), 1);
+    }
+
+    #[test]
+    fn game_time_is_frozen_after_the_credits() {
+        let mut settings = default_settings();
+        settings.override_game_time = true;
+        settings.wt_end_source = WorldTourEndSource::CreditsFlag;
+        let mut watchers = racing_watchers();
+        set(
+            &mut watchers.game_mode,
+            GameMode::WorldTour,
+            GameMode::WorldTour,
+        );
+        watchers.game_mode_valid = true;
+        set(&mut watchers.required_laps, 3, 3);
+        set(&mut watchers.end_credits, false, false);
+        play_race(
+            &mut watchers,
+            &settings,
+            Duration::seconds(95),
+            Duration::seconds(90),
+        );
+
+        set(&mut watchers.end_credits, false, true);
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert!(auto_split(&mut watchers, &settings));
+        assert!(watchers.run_ended);
+        let final_time = game_time(&watchers, &settings);
+        assert!(final_time == Some(Duration::seconds(90)));
+
+        // Races played after the credits don't change the time
+        set(&mut watchers.end_credits, true, false);
+        play_race(
+            &mut watchers,
+            &settings,
+            Duration::seconds(50),
+            Duration::seconds(48),
+        );
+        assert!(game_time(&watchers, &settings) == final_time);
+        assert!(!auto_split(&mut watchers, &settings));
+    }
+
+    #[test]
+    fn split_timings_order_the_load_change() {
+        for (split_timing, order) in [
+            (SplitTiming::AfterLoadChange, ["load change", "split"]),
+            (SplitTiming::BeforeLoadChange, ["split", "load change"]),
+        ] {
+            let actions = core::cell::RefCell::new(Vec::new());
+            split_timing.sequence(
+                || actions.borrow_mut().push("load change"),
+                || actions.borrow_mut().push("split"),
+            );
+            assert_eq!(actions.into_inner(), order);
+        }
+    }
+
+    #[test]
+    fn out_of_range_star_blocks_are_rejected() {
+        for world in 0..WORLD_TOUR_WORLDS.len() {
+            let events = world_events(world);
+            let mut stars = [0xFF; 0x719];
+            for event in events.clone() {
+                stars[STAR_OFFSETS[event - events.start]] = WORLD_TOUR_EVENT_MAX_STARS[event];
+            }
+            // Only the star ranks of the events of the world are checked
+            assert!(star_block_valid(&stars, world));
+
+            for event in events.clone() {
+                let mut freed = stars;
+                freed[STAR_OFFSETS[event - events.start]] = WORLD_TOUR_EVENT_MAX_STARS[event] + 1;
+                assert!(!star_block_valid(&freed, world));
+            }
+        }
+        assert!(star_block_valid(&[0; 0x719], 0));
+    }
+
+    #[test]
+    fn race_timer_reset_during_the_countdown_isnt_accumulated() {
+        let settings = default_settings();
+        let mut watchers = racing_watchers();
+        set(&mut watchers.race_status, 3, 3);
+        set(&mut watchers.igt, Duration::ZERO, Duration::ZERO);
+        update_state(&mut watchers, &settings, TimerState::Running);
+
+        for _ in 0..3 {
+            set(
+                &mut watchers.igt,
+                Duration::ZERO,
+                Duration::milliseconds(30),
+            );
+            update_state(&mut watchers, &settings, TimerState::Running);
+            set(
+                &mut watchers.igt,
+                Duration::milliseconds(30),
+                Duration::ZERO,
+            );
+            update_state(&mut watchers, &settings, TimerState::Running);
+            assert_eq!(watchers.total_igt, Duration::ZERO);
+            assert!(watchers
+                .race_exit
+                .pair
+                .is_some_and(|value| value.current == RaceExit::None));
+        }
+
+        // Restarting the race once it's running does count the time spent in it
+        set(&mut watchers.race_status, 3, RACE_STATUS_RACING);
+        set(&mut watchers.igt, Duration::ZERO, Duration::seconds(20));
+        update_state(&mut watchers, &settings, TimerState::Running);
+        set(&mut watchers.race_status, RACE_STATUS_RACING, 3);
+        set(&mut watchers.igt, Duration::seconds(20), Duration::ZERO);
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert_eq!(watchers.total_igt, Duration::seconds(20));
+        assert!(watchers
+            .race_exit
+            .pair
+            .is_some_and(|value| value.current == RaceExit::Retry));
+    }
+
+    #[test]
+    fn start_after_a_reset_needs_run_start_to_cycle() {
+        let mut settings = default_settings();
+        settings.reset = true;
+        let mut watchers = racing_watchers();
+        watchers.ticks_since_timer_start = 5 * 60 * DEFAULT_TICK_RATE as u32;
+        set(&mut watchers.run_start, false, true);
+        update_state(&mut watchers, &settings, TimerState::Running);
+        watchers.quit_confirmed = true;
+        assert!(reset(&watchers, &settings, TimerState::Running));
+
+        // The race is still loaded after the timer has been reset
+        for _ in 0..3 {
+            set(&mut watchers.run_start, true, true);
+            update_state(&mut watchers, &settings, TimerState::NotRunning);
+            assert!(!start(&watchers, &settings));
+        }
+
+        set(&mut watchers.run_start, true, false);
+        update_state(&mut watchers, &settings, TimerState::NotRunning);
+        assert!(!start(&watchers, &settings));
+        set(&mut watchers.run_start, false, true);
+        update_state(&mut watchers, &settings, TimerState::NotRunning);
+        assert!(start(&watchers, &settings));
+    }
+
+    #[test]
+    fn quitting_a_race_discards_its_time() {
+        let settings = default_settings();
+        let mut watchers = racing_watchers();
+        set(&mut watchers.required_laps, 3, 3);
+        play_race(
+            &mut watchers,
+            &settings,
+            Duration::seconds(62),
+            Duration::seconds(60),
+        );
+
+        // The next race is quit from the pause menu midway
+        start_race(&mut watchers, &settings, Duration::seconds(40));
+        assert_eq!(watchers.progress_igt, Duration::seconds(100));
+
+        set(&mut watchers.race_status, RACE_STATUS_RACING, 0);
+        set(
+            &mut watchers.igt,
+            Duration::seconds(40),
+            Duration::seconds(40),
+        );
+        update_state(&mut watchers, &settings, TimerState::Running);
+        set(&mut watchers.race_status, 0, 0);
+        set(&mut watchers.run_start, true, false);
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert_eq!(watchers.total_igt, Duration::seconds(60));
+        assert_eq!(watchers.progress_igt, Duration::seconds(60));
+
+        // The race timer only goes back to zero once the next race loads
+        set(&mut watchers.run_start, false, true);
+        set(&mut watchers.igt, Duration::seconds(40), Duration::ZERO);
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert_eq!(watchers.total_igt, Duration::seconds(60));
+
+        play_race(
+            &mut watchers,
+            &settings,
+            Duration::seconds(52),
+            Duration::seconds(50),
+        );
+        assert_eq!(watchers.total_igt, Duration::seconds(110));
+    }
+
+    #[test]
+    fn results_screen_time_is_added_to_game_time() {
+        let mut settings = default_settings();
+        settings.count_results_time = true;
+        let mut watchers = racing_watchers();
+        set(&mut watchers.required_laps, 3, 3);
+
+        play_race(
+            &mut watchers,
+            &settings,
+            Duration::seconds(82),
+            Duration::milliseconds(80_250),
+        );
+        let on_results = game_time(&watchers, &settings).unwrap();
+
+        let tick = Duration::seconds_f64(1.0 / DEFAULT_TICK_RATE);
+        for _ in 0..120 {
+            update_state(&mut watchers, &settings, TimerState::Running);
+        }
+        assert_eq!(
+            game_time(&watchers, &settings),
+            Some(on_results + tick * 120)
+        );
+
+        // Leaving the results screen keeps the time spent on it
+        set(&mut watchers.race_completed, true, false);
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert_eq!(
+            game_time(&watchers, &settings),
+            Some(on_results + tick * 120)
+        );
+    }
+
+    #[test]
+    fn reset_grace_period_counts_from_the_timer_start() {
+        let mut settings = default_settings();
+        settings.reset = true;
+        let mut watchers = racing_watchers();
+        update_state(&mut watchers, &settings, TimerState::NotRunning);
+        watchers.quit_confirmed = true;
+
+        // No game time has been accumulated yet, so only the time since the start counts
+        let grace_ticks =
+            ResetGracePeriod::ThreeSeconds.milliseconds() as f64 / 1000.0 * DEFAULT_TICK_RATE;
+        for _ in 0..grace_ticks as u32 - 1 {
+            watchers.ticks_since_timer_start += 1;
+            assert!(!reset(&watchers, &settings, TimerState::Running));
+        }
+        watchers.ticks_since_timer_start += 1;
+        assert_eq!(watchers.progress_igt, Duration::ZERO);
+        assert!(reset(&watchers, &settings, TimerState::Running));
+
+        // Restarting the timer starts the grace period over
+        update_state(&mut watchers, &settings, TimerState::NotRunning);
+        update_state(&mut watchers, &settings, TimerState::Running);
+        watchers.quit_confirmed = true;
+        assert!(!reset(&watchers, &settings, TimerState::Running));
+    }
+
+    #[test]
+    fn boss_events_have_their_own_max_stars() {
+        for (event, &max_stars) in WORLD_TOUR_EVENT_MAX_STARS.iter().enumerate() {
+            let boss = event == FATAL_FINALE
+                || WORLD_TOUR_WORLDS
+                    .iter()
+                    .any(|&(_, first_event)| first_event == event + 1);
+            if boss {
+                assert_eq!(max_stars, WORLD_TOUR_BOSS_MAX_STARS);
+            } else {
+                assert_eq!(max_stars, WORLD_TOUR_MAX_STARS);
+            }
+        }
+        assert_eq!(
+            WORLD_TOUR_EVENT_MAX_STARS[ARCADE_ANNIHILATION],
+            WORLD_TOUR_BOSS_MAX_STARS
+        );
+    }
+
+    #[test]
+    fn world_splits_give_one_split_per_star_increase() {
+        for world_split in [
+            WorldTourWorldSplit::WorldEntry,
+            WorldTourWorldSplit::WorldSegment,
+        ] {
+            let mut settings = default_settings();
+            settings.wt_world_split = world_split;
+            let mut watchers = world_tour_watchers();
+            update_state(&mut watchers, &settings, TimerState::Running);
+
+            // Clearing the first event of a world is both a world transition and an event
+            // completion, which still only splits once
+            let events = [COASTAL_CRUISE, 1, CANYON_CARNAGE, 8, 9, 18];
+            for event in events {
+                set(watchers.world_tour_events_mut()[event], 0, 2);
+                update_state(&mut watchers, &settings, TimerState::Running);
+                let mut splits = usize::from(auto_split(&mut watchers, &settings));
+
+                hold_stars(&mut watchers);
+                for _ in 0..3 {
+                    update_state(&mut watchers, &settings, TimerState::Running);
+                    splits += usize::from(auto_split(&mut watchers, &settings));
+                }
+                assert_eq!(splits, 1);
+            }
+            assert_eq!(watchers.auto_splits, events.len() as u32);
+        }
+    }
+
+    #[test]
+    fn retried_race_only_splits_and_counts_once() {
+        let settings = default_settings();
+        let mut watchers = racing_watchers();
+        watchers.game_mode_valid = true;
+        set(&mut watchers.track_id, Tracks::OceanView, Tracks::OceanView);
+        set(&mut watchers.required_laps, 3, 3);
+        update_state(&mut watchers, &settings, TimerState::Running);
+
+        // The failed attempt is retried from the pause menu, without splitting
+        set(&mut watchers.igt, Duration::ZERO, Duration::seconds(20));
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert!(!auto_split(&mut watchers, &settings));
+        set(&mut watchers.race_status, RACE_STATUS_RACING, 3);
+        set(&mut watchers.igt, Duration::seconds(20), Duration::ZERO);
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert!(!auto_split(&mut watchers, &settings));
+        assert!(watchers
+            .race_exit
+            .pair
+            .is_some_and(|value| value.current == RaceExit::Retry));
+        assert_eq!(watchers.total_igt, Duration::seconds(20));
+
+        // The successful attempt splits once, adding its own time only
+        set(&mut watchers.race_status, 3, RACE_STATUS_RACING);
+        set(&mut watchers.igt, Duration::ZERO, Duration::seconds(30));
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert!(!auto_split(&mut watchers, &settings));
+        set(
+            &mut watchers.race_status,
+            RACE_STATUS_RACING,
+            RACE_STATUS_RACING,
+        );
+        set(
+            &mut watchers.igt,
+            Duration::seconds(30),
+            Duration::seconds(30),
+        );
+        set(
+            &mut watchers.total_race_time,
+            Duration::ZERO,
+            Duration::seconds(30),
+        );
+        set(&mut watchers.race_completed, false, true);
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert!(auto_split(&mut watchers, &settings));
+
+        set(&mut watchers.race_status, RACE_STATUS_RACING, 0);
+        set(&mut watchers.race_completed, true, true);
+        update_state(&mut watchers, &settings, TimerState::Running);
+        assert!(!auto_split(&mut watchers, &settings));
+        assert_eq!(watchers.auto_splits, 1);
+        assert_eq!(watchers.total_igt, Duration::seconds(50));
+        assert_eq!(watchers.progress_igt, Duration::seconds(50));
+    }
+}